@@ -12,6 +12,12 @@ fn main() {
         .unwrap();
     println!("cargo:rustc-link-search={}", out.display());
 
+    // ARMv6-M cores (Cortex-M0 and M0+) don’t have the DWT cycle counter
+    println!("cargo:rustc-check-cfg=cfg(armv6m)");
+    if env::var("TARGET").unwrap().starts_with("thumbv6m-") {
+        println!("cargo:rustc-cfg=armv6m");
+    }
+
     // Only re-run the build script when memory.x is changed,
     // instead of when any part of the source code changes.
     println!("cargo:rerun-if-changed=memory.x");
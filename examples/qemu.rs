@@ -3,13 +3,13 @@
 
 extern crate panic_halt;
 
-use cortex_m_systick_countdown::{ MillisCountDown, PollingSysTick, SysTickCalibration};
+use cortex_m_systick_countdown::{MillisCountDown, PollingSysTick, SysTickCalibration};
 
 use core::time::Duration;
 use embedded_hal::{blocking::delay::DelayMs, timer::CountDown};
 
-use cortex_m_rt::entry;
 use cortex_m::peripheral::Peripherals;
+use cortex_m_rt::entry;
 use nb::block;
 
 use cortex_m_semihosting::{debug, hprintln};
@@ -25,10 +25,10 @@ fn main() -> ! {
         PollingSysTick::new(peripherals.SYST, &SysTickCalibration::built_in().unwrap());
 
     hprintln!("Delaying 1s…").unwrap();
-    counter.delay_ms(1_000);
+    counter.delay_ms(1_000u32);
 
     hprintln!("Delaying 2s…").unwrap();
-    counter.delay_ms(2_000);
+    counter.delay_ms(2_000u32);
 
     hprintln!("Looping for 10s…").unwrap();
 
@@ -38,7 +38,7 @@ fn main() -> ! {
     count_10s.start(Duration::from_secs(10));
 
     loop {
-        if let Ok(_) = count_10s.wait() {
+        if count_10s.wait().is_ok() {
             break;
         }
 
@@ -51,5 +51,7 @@ fn main() -> ! {
 
     debug::exit(debug::EXIT_SUCCESS);
 
-    loop {}
+    loop {
+        cortex_m::asm::wfi();
+    }
 }
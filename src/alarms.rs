@@ -0,0 +1,191 @@
+use core::cell::Cell;
+
+use crate::critical::{self, Mutex};
+use crate::{interrupt, CountsMillis, Instant};
+
+/// When an alarm goes off, and what it calls.
+type Alarm = (Instant, fn());
+
+/// Identifies an alarm set on an [`Alarms`](struct.Alarms.html), so that it
+/// can be cancelled.
+///
+/// Along with the slot the alarm is in, it records which use of that slot it’s
+/// for, so that an id kept after its alarm has gone off can’t cancel a later
+/// alarm that was given the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlarmId {
+    index: usize,
+    generation: u32,
+}
+
+/// Errors from [`Alarms`](struct.Alarms.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmsError {
+    /// `N` alarms are already set.
+    Full,
+}
+
+/// Fixed-capacity set of one-shot `fn()` callbacks, each with its own
+/// deadline
+///
+/// For the “turn off the relay in 30 seconds” sort of job, where keeping a
+/// countdown around and checking it from the main loop is more bookkeeping
+/// than the job is worth. Since it only needs `&self`, an `Alarms` can be a
+/// `static` shared between the main loop and the SysTick exception.
+///
+/// With an [`InterruptSysTick`](struct.InterruptSysTick.html), the callbacks
+/// can run from the exception handler:
+///
+/// ```ignore
+/// static ALARMS: Alarms<8> = Alarms::new();
+///
+/// #[exception]
+/// fn SysTick() {
+///     InterruptSysTick::on_interrupt();
+///     ALARMS.on_interrupt();
+/// }
+///
+/// ALARMS.schedule_after(&systick, 30_000, relay_off).unwrap();
+/// ```
+///
+/// With a polling counter, call [`poll`](#method.poll) from the main loop
+/// instead:
+///
+/// ```ignore
+/// loop {
+///     ALARMS.poll(&systick);
+/// }
+/// ```
+///
+/// Callbacks are plain `fn`s, so they can’t capture anything: keep their state
+/// in `static`s. Deadlines must be less than about 24.8 days away.
+pub struct Alarms<const N: usize> {
+    slots: Mutex<[Cell<Option<Alarm>>; N]>,
+    /// Bumped each time a slot is emptied, to invalidate its old ids.
+    generations: Mutex<[Cell<u32>; N]>,
+}
+
+impl<const N: usize> Alarms<N> {
+    /// Creates a set with no alarms. `const`, so that the result can be a
+    /// `static`.
+    pub const fn new() -> Self {
+        Alarms {
+            slots: Mutex::new([const { Cell::new(None) }; N]),
+            generations: Mutex::new([const { Cell::new(0) }; N]),
+        }
+    }
+
+    /// Calls `callback` once the time reaches `at`.
+    ///
+    /// If `at` has already passed, `callback` is called on the next
+    /// [`poll`](#method.poll) or [`on_interrupt`](#method.on_interrupt).
+    pub fn schedule_at(&self, at: Instant, callback: fn()) -> Result<AlarmId, AlarmsError> {
+        critical::free(|cs| {
+            let slots = self.slots.borrow(cs);
+            let i = slots
+                .iter()
+                .position(|slot| slot.get().is_none())
+                .ok_or(AlarmsError::Full)?;
+
+            slots[i].set(Some((at, callback)));
+
+            Ok(AlarmId {
+                index: i,
+                generation: self.generations.borrow(cs)[i].get(),
+            })
+        })
+    }
+
+    /// Calls `callback` once `ms` milliseconds have passed on `counter`.
+    pub fn schedule_after<CM: CountsMillis>(
+        &self,
+        counter: &CM,
+        ms: u32,
+        callback: fn(),
+    ) -> Result<AlarmId, AlarmsError> {
+        self.schedule_at(Instant::now(counter) + ms, callback)
+    }
+
+    /// Cancels an alarm. Returns false if it had already gone off or been
+    /// cancelled.
+    pub fn cancel(&self, id: AlarmId) -> bool {
+        critical::free(|cs| match self.generations.borrow(cs).get(id.index) {
+            Some(generation) if generation.get() == id.generation => {
+                release(&self.slots.borrow(cs)[id.index], generation)
+            }
+            _ => false,
+        })
+    }
+
+    /// Returns how many alarms are waiting to go off.
+    pub fn len(&self) -> usize {
+        critical::free(|cs| {
+            self.slots
+                .borrow(cs)
+                .iter()
+                .filter(|slot| slot.get().is_some())
+                .count()
+        })
+    }
+
+    /// Returns true if no alarms are waiting to go off.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Calls every alarm that is due by `counter`, and returns how many were
+    /// called.
+    pub fn poll<CM: CountsMillis>(&self, counter: &CM) -> usize {
+        self.run_due(Instant::now(counter))
+    }
+
+    /// Calls every alarm that is due by the `InterruptSysTick` count.
+    ///
+    /// This must only be called from your program’s SysTick exception
+    /// handler, after `InterruptSysTick::on_interrupt`.
+    pub fn on_interrupt(&self) {
+        self.run_due(interrupt::now());
+    }
+
+    fn run_due(&self, now: Instant) -> usize {
+        let mut ran = 0;
+
+        for i in 0..N {
+            // Each alarm is taken out inside the critical section but called
+            // outside of it, so that callbacks can set new alarms.
+            let due = critical::free(|cs| match self.slots.borrow(cs)[i].get() {
+                Some((at, callback)) if now >= at => {
+                    release(&self.slots.borrow(cs)[i], &self.generations.borrow(cs)[i]);
+                    Some(callback)
+                }
+                _ => None,
+            });
+
+            if let Some(callback) = due {
+                callback();
+                ran += 1;
+            }
+        }
+
+        ran
+    }
+}
+
+/// Empties `slot`, moving on to its next generation. Returns false if it was
+/// already empty.
+fn release(slot: &Cell<Option<Alarm>>, generation: &Cell<u32>) -> bool {
+    if slot.take().is_none() {
+        return false;
+    }
+
+    generation.set(generation.get().wrapping_add(1));
+    true
+}
+
+impl<const N: usize> Default for Alarms<N> {
+    fn default() -> Self {
+        Alarms::new()
+    }
+}
@@ -0,0 +1,178 @@
+//! Async support, enabled by the `async` feature.
+
+use core::future::Future;
+use core::num::Wrapping;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use embedded_hal_async::delay::DelayNs;
+use futures_core::Stream;
+
+use crate::{wakers, CountsMillis64, InterruptSysTick, Interval};
+
+/// Future that completes once a counter reaches a particular millisecond
+///
+/// While it waits, its waker is stored for the SysTick exception to wake:
+/// [`InterruptSysTick::on_interrupt`](struct.InterruptSysTick.html#method.on_interrupt)
+/// does this automatically. With a counter that doesn’t use the exception,
+/// such as `PollingSysTick`, call [`wake_expired_timers`](fn.wake_expired_timers.html)
+/// regularly (from your executor’s idle loop, for example) instead.
+///
+/// ```ignore
+/// TimerFuture::after_ms(&systick, 500).await;
+/// ```
+///
+/// At most 8 timers can be waiting for a wake-up at once. Any more ask their
+/// executor to poll them again right away, so they still work, but spin.
+pub struct TimerFuture<'a, CM: CountsMillis64> {
+    counter: &'a CM,
+    wake_at: u64,
+}
+
+impl<'a, CM: CountsMillis64> TimerFuture<'a, CM> {
+    /// Completes once at least `ms` milliseconds have passed.
+    ///
+    /// Same semantics as `MillisCountDown`: completes once the count is past
+    /// the current count plus `ms`, so the wait is at least `ms` long no
+    /// matter how far into the current millisecond we are.
+    pub fn after_ms(counter: &'a CM, ms: u32) -> Self {
+        let wake_at = counter.count64() + u64::from(ms) + 1;
+        TimerFuture::at(counter, wake_at)
+    }
+
+    /// Completes once the 64-bit count (see
+    /// [`count64`](trait.CountsMillis64.html#tymethod.count64)) reaches
+    /// `count`.
+    pub fn at(counter: &'a CM, count: u64) -> Self {
+        TimerFuture {
+            counter,
+            wake_at: count,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.counter.count64() >= self.wake_at
+    }
+}
+
+impl<'a, CM: CountsMillis64> Future for TimerFuture<'a, CM> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_expired() {
+            return Poll::Ready(());
+        }
+
+        // If there’s no room to wait for the SysTick exception, fall back to
+        // asking to be polled again right away.
+        if !wakers::register(self.wake_at, cx.waker()) {
+            cx.waker().wake_by_ref();
+        }
+
+        // Check again in case the exception fired between our first check and
+        // registering, in which case it won’t have woken us.
+        if self.is_expired() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Async source of ticks every `period_ms` milliseconds
+///
+/// Like [`PeriodicMillisCountDown`](struct.PeriodicMillisCountDown.html),
+/// each tick is timed from when the previous one was due, so a loop that
+/// awaits [`tick`](#method.tick) runs at a fixed rate without drifting.
+///
+/// ```ignore
+/// let mut ticker = Ticker::every_ms(&systick, 100);
+///
+/// loop {
+///     ticker.tick().await;
+///     sample_sensor();
+/// }
+/// ```
+pub struct Ticker<'a, CM: CountsMillis64> {
+    counter: &'a CM,
+    period_ms: u32,
+    next_at: u64,
+}
+
+impl<'a, CM: CountsMillis64> Ticker<'a, CM> {
+    /// Creates a `Ticker` whose first tick is `period_ms` from now.
+    pub fn every_ms(counter: &'a CM, period_ms: u32) -> Self {
+        Ticker {
+            counter,
+            period_ms,
+            next_at: counter.count64() + u64::from(period_ms),
+        }
+    }
+
+    /// Returns a future that completes at the next tick.
+    ///
+    /// If the ticker has fallen behind, the futures for missed ticks complete
+    /// immediately until it catches up.
+    pub fn tick(&mut self) -> TimerFuture<'a, CM> {
+        let at = self.next_at;
+        self.next_at += u64::from(self.period_ms);
+
+        TimerFuture::at(self.counter, at)
+    }
+
+    /// Starts the ticks over, with the next one `period_ms` from now.
+    pub fn reset(&mut self) {
+        self.next_at = self.counter.count64() + u64::from(self.period_ms);
+    }
+}
+
+impl<'a, CM: CountsMillis64> Stream for Interval<'a, CM> {
+    type Item = ();
+
+    /// Yields `()` once per period. Never ends.
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        if self.poll_tick().is_ok() {
+            return Poll::Ready(Some(()));
+        }
+
+        // Convert the wrapping tick time into the 64-bit count that wakers use.
+        let now = self.counter.count64();
+        let wake_at = now + u64::from((self.next_tick - Wrapping(now as u32)).0) + 1;
+
+        if !wakers::register(wake_at, cx.waker()) {
+            cx.waker().wake_by_ref();
+        }
+
+        if self.poll_tick().is_ok() {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Wakes any [`TimerFuture`](struct.TimerFuture.html)s whose times have been
+/// reached on `counter`.
+///
+/// `InterruptSysTick` does this from its exception handler, so only call this
+/// when timing with some other counter. It needs to be called at least as
+/// often as you want timers to be accurate to.
+pub fn wake_expired_timers<CM: CountsMillis64>(counter: &CM) {
+    wakers::wake_expired(counter.count64());
+}
+
+impl DelayNs for InterruptSysTick {
+    /// Waits for the SysTick exception, so delays are rounded up to the next
+    /// whole millisecond.
+    async fn delay_ns(&mut self, ns: u32) {
+        TimerFuture::after_ms(self, ns.div_ceil(1_000_000)).await
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        TimerFuture::after_ms(self, us.div_ceil(1_000)).await
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        TimerFuture::after_ms(self, ms).await
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{CountDownError, CountsMillis, MillisCountDown};
+
+/// Exponentially growing delays between retries
+///
+/// Each call to [`start_next`](#method.start_next) starts a countdown for the
+/// next delay in the sequence `base_ms`, `base_ms * multiplier`,
+/// `base_ms * multiplier²`, …, capped at `max_ms`. Wait on it with
+/// [`wait`](#method.wait), and once the operation finally succeeds, call
+/// [`reset`](#method.reset) so the next failure starts over from `base_ms`.
+///
+/// ```ignore
+/// let mut backoff = Backoff::new(&systick, 100, 2, 10_000);
+///
+/// while radio.join().is_err() {
+///     backoff.start_next();
+///     nb::block!(backoff.wait()).unwrap();
+/// }
+///
+/// backoff.reset();
+/// ```
+pub struct Backoff<'a, CM: CountsMillis> {
+    count_down: MillisCountDown<'a, CM>,
+    base_ms: u32,
+    multiplier: u32,
+    max_ms: u32,
+    next_ms: u32,
+    attempts: u32,
+}
+
+impl<'a, CM: CountsMillis> Backoff<'a, CM> {
+    /// Creates a `Backoff` whose first delay is `base_ms`, with each one after
+    /// that `multiplier` times longer than the last, up to `max_ms`.
+    pub fn new(counter: &'a CM, base_ms: u32, multiplier: u32, max_ms: u32) -> Self {
+        Backoff {
+            count_down: MillisCountDown::new(counter),
+            base_ms,
+            multiplier,
+            max_ms,
+            next_ms: base_ms.min(max_ms),
+            attempts: 0,
+        }
+    }
+
+    /// Starts waiting for the next delay in the sequence, returning how long
+    /// it is.
+    pub fn start_next(&mut self) -> u32 {
+        let delay_ms = self.next_ms;
+
+        self.count_down.start_ms(delay_ms);
+        self.next_ms = delay_ms.saturating_mul(self.multiplier).min(self.max_ms);
+        self.attempts = self.attempts.saturating_add(1);
+
+        delay_ms
+    }
+
+    /// Returns `Ok` once the delay started by
+    /// [`start_next`](#method.start_next) has passed, and
+    /// `nb::Error::WouldBlock` until then.
+    ///
+    /// Returns
+    /// [`nb::Error::Other(CountDownError::NotStarted)`](enum.CountDownError.html)
+    /// if no delay is running.
+    pub fn wait(&mut self) -> nb::Result<(), CountDownError> {
+        self.count_down.try_wait_ms()
+    }
+
+    /// Returns how many delays have been started since the last
+    /// [`reset`](#method.reset).
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Starts the sequence over from `base_ms`. Call this after a success.
+    pub fn reset(&mut self) {
+        // Not having a delay running is fine here
+        self.count_down.cancel().ok();
+        self.next_ms = self.base_ms.min(self.max_ms);
+        self.attempts = 0;
+    }
+}
@@ -0,0 +1,189 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Output};
+
+/// Ready-made patterns for [`BlinkPattern::play`](struct.BlinkPattern.html#method.play).
+pub mod blink_patterns {
+    /// “SOS” in Morse code, with 200ms dots.
+    pub const SOS: &[u32] = &[
+        200, 200, 200, 200, 200, 600, // S
+        600, 200, 600, 200, 600, 600, // O
+        200, 200, 200, 200, 200, 1_400, // S
+    ];
+
+    /// Two quick blinks a second, like a heartbeat.
+    pub const HEARTBEAT: &[u32] = &[100, 100, 100, 700];
+
+    /// On and off once a second.
+    pub const SLOW: &[u32] = &[500, 500];
+
+    /// On and off five times a second.
+    pub const FAST: &[u32] = &[100, 100];
+}
+
+/// How long each blink of an error code is on, and the gap between them.
+const CODE_BLINK_MS: u32 = 250;
+
+/// Gap after the last blink of an error code, before it repeats.
+const CODE_PAUSE_MS: u32 = 1_500;
+
+#[derive(Clone, Copy)]
+enum Steps<'p> {
+    Durations(&'p [u32]),
+    /// `count` short blinks followed by a pause.
+    ErrorCode(u8),
+}
+
+impl<'p> Steps<'p> {
+    fn len(self) -> usize {
+        match self {
+            Steps::Durations(durations) => durations.len(),
+            Steps::ErrorCode(count) => usize::from(count) * 2,
+        }
+    }
+
+    fn duration_ms(self, step: usize) -> u32 {
+        match self {
+            Steps::Durations(durations) => durations[step],
+            Steps::ErrorCode(_) if step + 1 == self.len() => CODE_PAUSE_MS,
+            Steps::ErrorCode(_) => CODE_BLINK_MS,
+        }
+    }
+}
+
+/// Plays on/off sequences on an output, such as a status LED
+///
+/// A pattern is a list of durations in milliseconds, alternating on and off
+/// and starting with on, so `&[100, 900]` is a short blink every second. Start
+/// one with [`play`](#method.play) (or [`play_error_code`](#method.play_error_code)
+/// to blink a number), then call [`poll`](#method.poll) often from your main
+/// loop to step through it.
+///
+/// ```ignore
+/// let mut status = BlinkPattern::new(&systick, PinOutput(led));
+/// status.play(blink_patterns::HEARTBEAT, true);
+///
+/// loop {
+///     if let Err(err) = step() {
+///         status.play_error_code(err.code(), true);
+///     }
+///
+///     status.poll();
+/// }
+/// ```
+///
+/// Each step is timed from when the previous one was due, so a repeating
+/// pattern keeps its rhythm even if `poll` is called late now and then.
+pub struct BlinkPattern<'a, 'p, CM: CountsMillis, O: Output> {
+    counter: &'a CM,
+    output: O,
+    steps: Steps<'p>,
+    repeat: bool,
+    step: usize,
+    step_started: Wrapping<u32>,
+    playing: bool,
+}
+
+impl<'a, 'p, CM: CountsMillis, O: Output> BlinkPattern<'a, 'p, CM, O> {
+    /// Creates a player for `output`, timed with `counter`, that isn’t
+    /// playing anything yet. Switches `output` off.
+    pub fn new(counter: &'a CM, mut output: O) -> Self {
+        output.set(false);
+
+        BlinkPattern {
+            counter,
+            output,
+            steps: Steps::Durations(&[]),
+            repeat: false,
+            step: 0,
+            step_started: Wrapping(0),
+            playing: false,
+        }
+    }
+
+    /// Starts playing `pattern` from the beginning, replacing whatever was
+    /// playing. If `repeat` is false, the output is switched off once the
+    /// pattern ends.
+    pub fn play(&mut self, pattern: &'p [u32], repeat: bool) {
+        self.start(Steps::Durations(pattern), repeat);
+    }
+
+    /// Starts blinking `code` times followed by a pause, so that the number
+    /// can be counted by eye. A `code` of 0 leaves the output off.
+    pub fn play_error_code(&mut self, code: u8, repeat: bool) {
+        self.start(Steps::ErrorCode(code), repeat);
+    }
+
+    fn start(&mut self, steps: Steps<'p>, repeat: bool) {
+        self.steps = steps;
+        self.repeat = repeat;
+        self.step = 0;
+        self.step_started = self.counter.count();
+        self.playing = steps.len() > 0;
+        self.output.set(self.playing);
+    }
+
+    /// Stops playing and switches the output off.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.output.set(false);
+    }
+
+    /// Returns true until a pattern that doesn’t repeat has ended.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Moves on to the next step if the current one is over. Returns true
+    /// while a pattern is playing.
+    pub fn poll(&mut self) -> bool {
+        let now = self.counter.count();
+
+        // Catches up at most one time through the pattern, which also keeps
+        // a repeating pattern of all zeros from spinning forever.
+        for _ in 0..self.steps.len() {
+            if !self.playing {
+                break;
+            }
+
+            let step_end = self.step_started + Wrapping(self.steps.duration_ms(self.step));
+
+            if !has_passed(now, step_end) {
+                break;
+            }
+
+            self.step_started = step_end;
+            self.step += 1;
+
+            if self.step == self.steps.len() {
+                if !self.repeat {
+                    self.stop();
+                    break;
+                }
+
+                self.step = 0;
+            }
+
+            self.output.set(self.step & 1 == 0);
+        }
+
+        // If polling fell more than a whole pattern behind, start the current
+        // step over rather than rushing through the rest to catch up.
+        if self.playing
+            && has_passed(
+                now,
+                self.step_started + Wrapping(self.steps.duration_ms(self.step)),
+            )
+        {
+            self.step_started = now;
+        }
+
+        self.playing
+    }
+
+    /// Returns the output, switched off.
+    pub fn free(mut self) -> O {
+        self.stop();
+        self.output
+    }
+}
@@ -0,0 +1,211 @@
+use core::marker::PhantomData;
+
+use cortex_m::peripheral::{syst::SystClkSource, SYST};
+
+use crate::{
+    CalibrationError, InterruptSysTick, PollingSysTick, Running, Stopped, SysTickCalibration,
+    SysTickPeripheral,
+};
+
+/// Configuration for a [`PollingSysTick`](struct.PollingSysTick.html)
+///
+/// Get one from [`PollingSysTick::builder`](struct.PollingSysTick.html#method.builder),
+/// change whatever you need to, and then call [`build`](#method.build). The
+/// defaults are the same as [`PollingSysTick::new`](struct.PollingSysTick.html#method.new):
+/// the core clock, 1ms ticks, no exception, started right away, and counting
+/// from 0.
+///
+/// ```ignore
+/// let systick = PollingSysTick::builder()
+///     .external_clock()
+///     .tick_ms(10)
+///     .build(peripherals.SYST, &SysTickCalibration::from_clock_hz(32_768)?)?;
+/// ```
+///
+/// `S` is the state that `build` gives the `PollingSysTick`:
+/// [`Running`](struct.Running.html) unless
+/// [`start_stopped`](#method.start_stopped) was called.
+#[derive(Clone, Debug)]
+pub struct PollingSysTickBuilder<S = Running> {
+    pub(crate) clock_source: SystClkSource,
+    pub(crate) tick_ms: u32,
+    pub(crate) interrupt: bool,
+    pub(crate) enabled: bool,
+    pub(crate) initial_count: u64,
+    pub(crate) trim_ppm: i32,
+    state: PhantomData<S>,
+}
+
+impl PollingSysTickBuilder {
+    pub(crate) fn new() -> Self {
+        PollingSysTickBuilder {
+            clock_source: SystClkSource::Core,
+            tick_ms: 1,
+            interrupt: false,
+            enabled: true,
+            initial_count: 0,
+            trim_ppm: 0,
+            state: PhantomData,
+        }
+    }
+
+    /// Leaves SysTick configured but not counting once it’s built, so that
+    /// [`build`](#method.build) gives a `PollingSysTick<Stopped>`. Call
+    /// [`start`](struct.PollingSysTick.html#method.start) on it when it’s
+    /// time to count.
+    pub fn start_stopped(self) -> PollingSysTickBuilder<Stopped> {
+        PollingSysTickBuilder {
+            clock_source: self.clock_source,
+            tick_ms: self.tick_ms,
+            interrupt: self.interrupt,
+            enabled: false,
+            initial_count: self.initial_count,
+            trim_ppm: self.trim_ppm,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<S> PollingSysTickBuilder<S> {
+    /// Clocks SysTick from the external reference clock (STCLK) rather than
+    /// the core clock.
+    ///
+    /// Many vendors route a fixed reference clock here, which keeps running at
+    /// the same rate when the core clock is changed and may be more stable.
+    /// Make the calibration from its frequency, which is usually listed in the
+    /// reference manual’s clock tree.
+    pub fn external_clock(mut self) -> Self {
+        self.clock_source = SystClkSource::External;
+        self
+    }
+
+    /// Has SysTick wrap only every `tick_ms` milliseconds, with the count
+    /// going up by `tick_ms` each time.
+    ///
+    /// Use this on slow or low-power systems to poll less often: `count` only
+    /// needs to be called once per tick. Timing is only as fine as the tick,
+    /// though [`count_micros`](trait.CountsMicros.html) still interpolates
+    /// within it.
+    pub fn tick_ms(mut self, tick_ms: u32) -> Self {
+        self.tick_ms = tick_ms;
+        self
+    }
+
+    /// Enables the SysTick exception, so that it fires every tick.
+    ///
+    /// `PollingSysTick` doesn’t need the exception to count, but it’s useful
+    /// for waking the processor from `wfi`. Your program must define a SysTick
+    /// handler if you turn this on (the `rt` feature’s will do), and the
+    /// handler mustn’t read SysTick’s control register, since that clears the
+    /// flag `PollingSysTick` counts wraps with.
+    pub fn interrupt(mut self, interrupt: bool) -> Self {
+        self.interrupt = interrupt;
+        self
+    }
+
+    /// Sets the count to start from, such as time saved from before a reset,
+    /// or `u32::MAX as u64 - 10` to see the count wrap ten milliseconds in.
+    pub fn initial_count(mut self, ms: u64) -> Self {
+        self.initial_count = ms;
+        self
+    }
+
+    /// Starts with a trim, as set by
+    /// [`set_trim_ppm`](struct.PollingSysTick.html#method.set_trim_ppm).
+    pub fn trim_ppm(mut self, ppm: i32) -> Self {
+        self.trim_ppm = ppm;
+        self
+    }
+
+    /// Configures SysTick and makes the `PollingSysTick`.
+    ///
+    /// If SysTick can’t be set up as asked, such as when the tick doesn’t fit
+    /// in its 24-bit reload register or the chip has no external reference
+    /// clock, returns `syst` back along with the reason.
+    pub fn build<P: SysTickPeripheral>(
+        self,
+        syst: P,
+        calibration: &SysTickCalibration,
+    ) -> Result<PollingSysTick<S>, (SYST, CalibrationError)> {
+        let syst = syst.into_syst();
+
+        if self.clock_source == SystClkSource::External && !SYST::has_reference_clock() {
+            return Err((syst, CalibrationError::NoReferenceClock));
+        }
+
+        let tick = match calibration
+            .reload_for_tick_ms(self.tick_ms)
+            .and_then(|tick| Ok((tick, tick.trimmed(self.trim_ppm)?)))
+        {
+            Ok(tick) => tick,
+            Err(err) => return Err((syst, err)),
+        };
+
+        Ok(PollingSysTick::configure(syst, calibration, tick.0, tick.1, &self).into_state())
+    }
+}
+
+/// Configuration for an [`InterruptSysTick`](struct.InterruptSysTick.html)
+///
+/// Get one from [`InterruptSysTick::builder`](struct.InterruptSysTick.html#method.builder).
+/// The defaults are the same as [`InterruptSysTick::new`](struct.InterruptSysTick.html#method.new):
+/// counting from 0, with the exception left at whatever priority it already
+/// had.
+///
+/// ```ignore
+/// let systick = unsafe { InterruptSysTick::builder().priority(0x80) }
+///     .build(cp.SYST, &calibration);
+/// ```
+#[derive(Clone, Debug)]
+pub struct InterruptSysTickBuilder {
+    pub(crate) initial_count: u64,
+    pub(crate) priority: Option<u8>,
+}
+
+impl InterruptSysTickBuilder {
+    pub(crate) fn new() -> Self {
+        InterruptSysTickBuilder {
+            initial_count: 0,
+            priority: None,
+        }
+    }
+
+    /// Sets the count to start from, such as time saved from before a reset.
+    /// Also handy for testing how your timeouts cope with the `u32` count
+    /// wrapping, by starting a few milliseconds before it does.
+    pub fn initial_count(mut self, ms: u64) -> Self {
+        self.initial_count = ms;
+        self
+    }
+
+    /// Sets the SysTick exception’s priority, in the SHPR3 register, before
+    /// the exception is enabled.
+    ///
+    /// Lower values are more urgent. Give time-critical interrupts a lower
+    /// value than this, so that the tick can’t hold them up, and background
+    /// ones a higher value, so that they can’t hold up the count. Only the
+    /// most significant bits are implemented, and how many depends on the
+    /// chip: with 4 bits, use multiples of 16. SysTick starts out at priority
+    /// 0, the most urgent.
+    ///
+    /// # Safety
+    ///
+    /// Like `SCB::set_priority`, changing priorities can break priority-based
+    /// critical sections, such as RTIC’s resource locks. `build` also writes
+    /// SHPR3 without an `SCB`, so nothing else may be changing system handler
+    /// priorities while it runs. Building during initialization is fine.
+    pub unsafe fn priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Configures SysTick, sets its exception’s priority if one was given, and
+    /// then starts it counting.
+    pub fn build<P: SysTickPeripheral>(
+        self,
+        syst: P,
+        calibration: &SysTickCalibration,
+    ) -> InterruptSysTick {
+        InterruptSysTick::configure(syst.into_syst(), calibration, &self)
+    }
+}
@@ -0,0 +1,148 @@
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal::spi::FullDuplex;
+
+use crate::{wait_with_deadline, CountsMillis, Instant, MillisCountDown, TimeoutError};
+
+/// SPI bus whose transactions give up after a deadline
+///
+/// Wraps an embedded-hal 0.2 `spi::FullDuplex<u8>`, and runs its `send` and
+/// `read` in a polling loop, so a peripheral that never finishes a transfer
+/// (from a clock misconfiguration, or a DMA channel that’s claimed the bus)
+/// returns `TimeoutError::TimedOut` instead of hanging. The limit is for the
+/// whole transaction, not each byte.
+///
+/// ```ignore
+/// let mut spi = TimedSpi::new(spi, &systick);
+///
+/// cs.set_low().ok();
+/// let result = spi.transfer(&mut buf, 10);
+/// cs.set_high().ok();
+/// ```
+///
+/// Blocking I2C traits in embedded-hal 0.2 have no `nb` equivalent to poll, so
+/// there’s no I2C version of this. To get a stuck I2C bus going again, use
+/// [`recover_i2c_bus`](fn.recover_i2c_bus.html).
+pub struct TimedSpi<'a, S, CM: CountsMillis> {
+    spi: S,
+    counter: &'a CM,
+}
+
+impl<'a, S: FullDuplex<u8>, CM: CountsMillis> TimedSpi<'a, S, CM> {
+    /// Wraps `spi`, timing with `counter`.
+    pub fn new(spi: S, counter: &'a CM) -> Self {
+        TimedSpi { spi, counter }
+    }
+
+    /// Returns the wrapped SPI bus, for using it directly.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.spi
+    }
+
+    /// Turns this value back into the wrapped SPI bus.
+    pub fn free(self) -> S {
+        self.spi
+    }
+
+    /// Sends each word in `words`, replacing it with the word read back, all
+    /// within `ms` milliseconds.
+    pub fn transfer<'w>(
+        &mut self,
+        words: &'w mut [u8],
+        ms: u32,
+    ) -> Result<&'w [u8], TimeoutError<S::Error>> {
+        let deadline = Instant::now(self.counter) + ms;
+
+        for word in words.iter_mut() {
+            *word = self.exchange(*word, deadline)?;
+        }
+
+        Ok(words)
+    }
+
+    /// Sends each word in `words`, discarding the words read back, all within
+    /// `ms` milliseconds.
+    pub fn write(&mut self, words: &[u8], ms: u32) -> Result<(), TimeoutError<S::Error>> {
+        let deadline = Instant::now(self.counter) + ms;
+
+        for word in words {
+            self.exchange(*word, deadline)?;
+        }
+
+        Ok(())
+    }
+
+    fn exchange(&mut self, word: u8, deadline: Instant) -> Result<u8, TimeoutError<S::Error>> {
+        let spi = &mut self.spi;
+
+        wait_with_deadline(self.counter, deadline, || spi.send(word))?;
+        wait_with_deadline(self.counter, deadline, || spi.read())
+    }
+}
+
+/// Frees an I2C bus that a peripheral is holding by keeping SDA low, as can
+/// happen when the controller resets partway through a read.
+///
+/// With the I2C peripheral disabled and its pins configured as GPIO (`scl` as
+/// an open-drain output), this clocks SCL up to 9 times, so that the stuck
+/// peripheral can finish sending whatever byte it was in the middle of, until
+/// it lets go of SDA. Each half of a clock takes a millisecond, which is slow
+/// but within what I2C allows.
+///
+/// Returns `TimeoutError::TimedOut` if SDA is still low after 9 clocks or
+/// `ms` milliseconds, whichever comes first. Afterwards, reinitialize the I2C
+/// peripheral, which gets the bus back to idle.
+///
+/// ```ignore
+/// if let Err(nb::Error::Other(i2c::Error::Timeout)) = result {
+///     let (scl, sda) = i2c.free();
+///     recover_i2c_bus(&systick, &mut scl.into_open_drain_output(), &sda, 50)?;
+///     i2c = I2c::new(scl.into_alternate(), sda.into_alternate(), ...);
+/// }
+/// ```
+pub fn recover_i2c_bus<CM, SCL, SDA, E>(
+    counter: &CM,
+    scl: &mut SCL,
+    sda: &SDA,
+    ms: u32,
+) -> Result<(), TimeoutError<E>>
+where
+    CM: CountsMillis,
+    SCL: OutputPin<Error = E>,
+    SDA: InputPin<Error = E>,
+{
+    let deadline = Instant::now(counter) + ms;
+
+    for _ in 0..9 {
+        if sda.is_high().map_err(TimeoutError::Other)? {
+            return Ok(());
+        }
+
+        scl.set_low().map_err(TimeoutError::Other)?;
+        half_clock(counter, deadline)?;
+        scl.set_high().map_err(TimeoutError::Other)?;
+        half_clock(counter, deadline)?;
+    }
+
+    if sda.is_high().map_err(TimeoutError::Other)? {
+        Ok(())
+    } else {
+        Err(TimeoutError::TimedOut)
+    }
+}
+
+/// Waits out half of a recovery clock, or fails if that would go past
+/// `deadline`.
+fn half_clock<CM: CountsMillis, E>(counter: &CM, deadline: Instant) -> Result<(), TimeoutError<E>> {
+    let mut half = MillisCountDown::new(counter);
+    half.start_ms(1);
+
+    loop {
+        if Instant::now(counter) > deadline {
+            return Err(TimeoutError::TimedOut);
+        }
+
+        if half.try_wait_ms().is_ok() {
+            return Ok(());
+        }
+    }
+}
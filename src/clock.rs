@@ -0,0 +1,43 @@
+//! Implementations of embedded-time’s `Clock` trait, enabled by the
+//! `embedded-time` feature.
+
+use embedded_time::clock::{Clock, Error};
+use embedded_time::fraction::Fraction;
+use embedded_time::Instant;
+
+use crate::{CountsMillis, InterruptSysTick, ManualTickCounter, PollingSysTick};
+
+impl Clock for PollingSysTick {
+    /// Same wrapping `u32` as [`count`](#method.count). embedded-time’s
+    /// `Instant` comparisons are wrap-aware, so durations of up to about 24.8
+    /// days can be measured.
+    type T = u32;
+
+    const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+    /// Never fails, but has the same requirement as [`count`](#method.count)
+    /// that it be polled at least once per millisecond.
+    fn try_now(&self) -> Result<Instant<Self>, Error> {
+        Ok(Instant::new(self.count().0))
+    }
+}
+
+impl Clock for InterruptSysTick {
+    type T = u32;
+
+    const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+    fn try_now(&self) -> Result<Instant<Self>, Error> {
+        Ok(Instant::new(self.count().0))
+    }
+}
+
+impl Clock for ManualTickCounter {
+    type T = u32;
+
+    const SCALING_FACTOR: Fraction = Fraction::new(1, 1_000);
+
+    fn try_now(&self) -> Result<Instant<Self>, Error> {
+        Ok(Instant::new(self.count().0))
+    }
+}
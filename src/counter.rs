@@ -0,0 +1,111 @@
+use core::num::Wrapping;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::critical;
+
+/// 64-bit millisecond count, stored as two 32-bit halves so that it works on
+/// targets without 64-bit atomics.
+///
+/// The low half is what [`CountsMillis`](../trait.CountsMillis.html) reports,
+/// and can be read on its own. Reading both halves uses a high-low-high
+/// protocol: if the high half changed while we were reading the low half then
+/// the low half rolled over, and we read again.
+pub(crate) struct Counter64 {
+    low: AtomicU32,
+    high: AtomicU32,
+}
+
+impl Counter64 {
+    pub const fn new() -> Self {
+        Counter64::starting_at(0)
+    }
+
+    pub const fn starting_at(count: u64) -> Self {
+        Counter64 {
+            low: AtomicU32::new(count as u32),
+            high: AtomicU32::new((count >> 32) as u32),
+        }
+    }
+
+    /// Adds one to the count.
+    ///
+    /// Callers must make sure that this can’t be called from two places at
+    /// once, either by calling it from inside a critical section or only from
+    /// the SysTick exception handler.
+    pub fn increment(&self) {
+        let low = self.low.load(Ordering::Relaxed).wrapping_add(1);
+
+        if low == 0 {
+            // Both halves need to change together so that a reader that
+            // preempts us can’t see the new low half with the old high half.
+            critical::free(|_| {
+                let high = self.high.load(Ordering::Relaxed);
+                self.high.store(high.wrapping_add(1), Ordering::Relaxed);
+                self.low.store(low, Ordering::Relaxed);
+            });
+        } else {
+            self.low.store(low, Ordering::Relaxed);
+        }
+    }
+
+    /// Adds `n` to the count, even if another context might be doing the same.
+    ///
+    /// On targets with atomic compare-and-swap (thumbv7 and up) this only needs
+    /// a critical section when the low half rolls over. Elsewhere (thumbv6) it
+    /// always uses one.
+    #[cfg(target_has_atomic = "32")]
+    pub fn add(&self, n: u32) {
+        let mut low = self.low.load(Ordering::Relaxed);
+
+        // Rolling over into the high half has to be done inside a critical
+        // section.
+        while let Some(new_low) = low.checked_add(n) {
+            match self
+                .low
+                .compare_exchange_weak(low, new_low, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(actual) => low = actual,
+            }
+        }
+
+        critical::free(|_| self.add_exclusive(n));
+    }
+
+    /// thumbv6 has no compare-and-swap, so the whole addition goes in a
+    /// critical section.
+    #[cfg(not(target_has_atomic = "32"))]
+    pub fn add(&self, n: u32) {
+        critical::free(|_| self.add_exclusive(n));
+    }
+
+    /// Adds `n` to the count. Callers must be inside a critical section.
+    fn add_exclusive(&self, n: u32) {
+        let count = self.get64().wrapping_add(u64::from(n));
+        self.high.store((count >> 32) as u32, Ordering::Relaxed);
+        self.low.store(count as u32, Ordering::Relaxed);
+    }
+
+    /// Sets the count to `count`.
+    pub fn set(&self, count: u64) {
+        critical::free(|_| {
+            self.high.store((count >> 32) as u32, Ordering::Relaxed);
+            self.low.store(count as u32, Ordering::Relaxed);
+        });
+    }
+
+    pub fn get(&self) -> Wrapping<u32> {
+        Wrapping(self.low.load(Ordering::Relaxed))
+    }
+
+    pub fn get64(&self) -> u64 {
+        loop {
+            let high = self.high.load(Ordering::Relaxed);
+            let low = self.low.load(Ordering::Relaxed);
+
+            if self.high.load(Ordering::Relaxed) == high {
+                return (u64::from(high) << 32) | u64::from(low);
+            }
+        }
+    }
+}
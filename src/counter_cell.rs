@@ -0,0 +1,94 @@
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{critical, CountsMillis, MillisCountDown};
+
+/// `MillisCountDown` on a counter that lives forever
+///
+/// Since it has no lifetime to carry around, this can be stored in a driver
+/// struct as easily as any other field. Get one from
+/// [`CounterCell::count_down`](struct.CounterCell.html#method.count_down), or
+/// make one from any `&'static` counter with `MillisCountDown::new`.
+pub type StaticCountDown<CM> = MillisCountDown<'static, CM>;
+
+/// Home in a `static` for a counter that’s created at runtime
+///
+/// Counters like `PollingSysTick` can’t be created until the clocks are set
+/// up, so they can’t go directly in a `static`. Put a `CounterCell` there
+/// instead and move the counter into it at startup. From then on it can be
+/// borrowed for `'static`, which makes
+/// [`StaticCountDown`](type.StaticCountDown.html)s that drivers can keep
+/// without any lifetime plumbing.
+///
+/// ```ignore
+/// static SYSTICK: CounterCell<PollingSysTick> = CounterCell::new();
+///
+/// let systick = SYSTICK.init(PollingSysTick::new(syst, &calibration)).ok().unwrap();
+/// let driver = Driver::new(spi, SYSTICK.count_down().unwrap());
+/// ```
+pub struct CounterCell<CM: CountsMillis> {
+    initialized: AtomicBool,
+    counter: UnsafeCell<MaybeUninit<CM>>,
+}
+
+// The counter is only written once, inside a critical section, before
+// `initialized` is set. After that it’s only ever shared, which is fine as long
+// as the counter itself is Sync. It can be moved in from another context, so
+// it needs to be Send as well.
+unsafe impl<CM: CountsMillis + Send + Sync> Sync for CounterCell<CM> {}
+
+impl<CM: CountsMillis> CounterCell<CM> {
+    /// Creates an empty `CounterCell`.
+    pub const fn new() -> Self {
+        CounterCell {
+            initialized: AtomicBool::new(false),
+            counter: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Moves `counter` into the cell and returns a `'static` reference to it.
+    ///
+    /// If the cell already has a counter, returns `counter` back as an error.
+    pub fn init(&'static self, counter: CM) -> Result<&'static CM, CM> {
+        critical::free(|_| {
+            if self.initialized.load(Ordering::Relaxed) {
+                return Err(counter);
+            }
+
+            // Safety: nothing can have a reference to the counter yet, since
+            // `initialized` is false, and we’re in a critical section so no
+            // one else can be initializing it at the same time.
+            unsafe { (*self.counter.get()).write(counter) };
+            self.initialized.store(true, Ordering::Release);
+
+            Ok(())
+        })?;
+
+        Ok(self.get().unwrap())
+    }
+
+    /// Returns the counter, or `None` if [`init`](#method.init) hasn’t been
+    /// called yet.
+    pub fn get(&'static self) -> Option<&'static CM> {
+        if self.initialized.load(Ordering::Acquire) {
+            // Safety: once `initialized` is set, the counter has been written
+            // and is never written again.
+            Some(unsafe { (*self.counter.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Creates a `StaticCountDown` on the counter, or returns `None` if
+    /// [`init`](#method.init) hasn’t been called yet.
+    pub fn count_down(&'static self) -> Option<StaticCountDown<CM>> {
+        self.get().map(MillisCountDown::new)
+    }
+}
+
+impl<CM: CountsMillis> Default for CounterCell<CM> {
+    fn default() -> Self {
+        CounterCell::new()
+    }
+}
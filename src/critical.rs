@@ -0,0 +1,19 @@
+//! The critical section used to guard the crate’s shared state.
+//!
+//! By default this is `cortex_m::interrupt::free`, which only disables
+//! interrupts on the current core. With the `critical-section` feature it’s the
+//! [`critical-section`](https://docs.rs/critical-section/1) crate instead, so
+//! that multi-core parts (or programs with their own locking) can supply an
+//! implementation that is actually exclusive.
+//!
+//! Both versions are called the same way: `free(|cs| MUTEX.borrow(cs))`.
+
+#[cfg(not(feature = "critical-section"))]
+pub(crate) use cortex_m::interrupt::free;
+#[cfg(not(feature = "critical-section"))]
+pub(crate) use cortex_m::interrupt::Mutex;
+
+#[cfg(feature = "critical-section")]
+pub(crate) use critical_section::with as free;
+#[cfg(feature = "critical-section")]
+pub(crate) use critical_section::Mutex;
@@ -0,0 +1,79 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Filters bounce out of a digital input such as a button or limit switch
+///
+/// Feed it raw samples with [`update`](#method.update). The debounced state
+/// only changes once the samples have been steady at the new level for
+/// `stable_ms` milliseconds.
+///
+/// ```ignore
+/// let mut button = Debouncer::new(&systick, 20, false);
+///
+/// loop {
+///     button.update(pin.is_high().unwrap());
+///
+///     if button.rising_edge() {
+///         toggle_led();
+///     }
+/// }
+/// ```
+pub struct Debouncer<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    stable_ms: u32,
+    state: bool,
+    last_sample: bool,
+    last_change: Wrapping<u32>,
+    changed: bool,
+}
+
+impl<'a, CM: CountsMillis> Debouncer<'a, CM> {
+    /// Creates a `Debouncer` whose input has to hold steady for `stable_ms`
+    /// before its state changes, starting from `initial`.
+    pub fn new(counter: &'a CM, stable_ms: u32, initial: bool) -> Self {
+        Debouncer {
+            counter,
+            stable_ms,
+            state: initial,
+            last_sample: initial,
+            last_change: counter.count(),
+            changed: false,
+        }
+    }
+
+    /// Takes a raw sample of the input and returns the debounced state.
+    ///
+    /// Call this regularly, at least as often as you want to notice changes.
+    pub fn update(&mut self, sample: bool) -> bool {
+        let now = self.counter.count();
+        self.changed = false;
+
+        if sample != self.last_sample {
+            self.last_sample = sample;
+            self.last_change = now;
+        } else if sample != self.state && (now - self.last_change).0 >= self.stable_ms {
+            self.state = sample;
+            self.changed = true;
+        }
+
+        self.state
+    }
+
+    /// Returns the debounced state.
+    pub fn is_high(&self) -> bool {
+        self.state
+    }
+
+    /// Returns true if the most recent [`update`](#method.update) changed the
+    /// debounced state from low to high.
+    pub fn rising_edge(&self) -> bool {
+        self.changed && self.state
+    }
+
+    /// Returns true if the most recent [`update`](#method.update) changed the
+    /// debounced state from high to low.
+    pub fn falling_edge(&self) -> bool {
+        self.changed && !self.state
+    }
+}
@@ -0,0 +1,40 @@
+//! Implementations of defmt’s `Format` trait that can’t be derived, enabled by
+//! the `defmt` feature, and the log timestamp from `defmt-timestamp`.
+
+use defmt::{Format, Formatter};
+
+use crate::{CountsMillis, CountsMillis64, Instant, MillisCountDown, MillisCountDown64};
+
+impl Format for Instant {
+    fn format(&self, f: Formatter) {
+        defmt::write!(f, "Instant({=u32}ms)", self.millis());
+    }
+}
+
+// Reading the counter to show the remaining time would update a
+// PollingSysTick as a side effect, so only the countdown’s own state is shown.
+impl<'a, CM: CountsMillis> Format for MillisCountDown<'a, CM> {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "MillisCountDown {{ deadline: {}, duration_ms: {} }}",
+            self.deadline(),
+            self.duration_ms
+        );
+    }
+}
+
+impl<'a, CM: CountsMillis64> Format for MillisCountDown64<'a, CM> {
+    fn format(&self, f: Formatter) {
+        defmt::write!(
+            f,
+            "MillisCountDown64 {{ deadline_ms: {} }}",
+            self.target_millis
+        );
+    }
+}
+
+// Timestamps every defmt log message with the global millisecond count. A
+// program can only have one timestamp, so this has its own feature.
+#[cfg(feature = "defmt-timestamp")]
+defmt::timestamp!("{=u32:ms}", crate::global::millis());
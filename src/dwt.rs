@@ -0,0 +1,141 @@
+use core::num::Wrapping;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_m::peripheral::{DCB, DWT};
+
+use crate::counter::Counter64;
+use crate::{critical, CountsMicros, CountsMillis, CountsMillis64};
+
+/// Millisecond counter based on the DWT cycle counter
+///
+/// This leaves SysTick free for an RTOS or HAL while still providing the
+/// [`CountsMillis`](trait.CountsMillis.html) and
+/// [`CountsMicros`](trait.CountsMicros.html) traits, so it can be the source
+/// for [`MillisCountDown`](struct.MillisCountDown.html) instances. The raw
+/// cycle count is also available from [`cycles`](#method.cycles) for
+/// profiling.
+///
+/// Not available on ARMv6-M (Cortex-M0 and M0+), which has no cycle counter.
+///
+/// ## Implementation
+///
+/// Like [`PollingSysTick`](struct.PollingSysTick.html), this counts by
+/// polling: each call to `count` turns the cycles since the last call into
+/// whole milliseconds, carrying the remainder over to the next call. The cycle
+/// counter is 32 bits, so it must be polled at least once every 2³² cycles
+/// (about 36 seconds at 120MHz), which is much less often than
+/// `PollingSysTick`’s once per millisecond.
+pub struct DwtCountsMillis {
+    dwt: DWT,
+    /// Cycles per millisecond as 32.32 fixed point, so that clocks that
+    /// aren’t a whole number of kHz don’t drift.
+    cycles_per_ms: u64,
+    /// Cycle count at the end of the last whole millisecond that was counted,
+    /// along with the fraction of a cycle past it in `last_fraction`.
+    last_cycles: AtomicU32,
+    last_fraction: AtomicU32,
+    counter: Counter64,
+}
+
+// DWT isn’t Sync, but nothing that takes &DwtCountsMillis uses it: cycles are
+// read through DWT::cycle_count, and the other fields are only updated inside a
+// critical section.
+unsafe impl Sync for DwtCountsMillis {}
+
+impl DwtCountsMillis {
+    /// Enables the cycle counter and starts counting from 0.
+    ///
+    /// `hz` is the core clock frequency, which is what the cycle counter
+    /// counts.
+    ///
+    /// Some ARMv7-M cores are built without a cycle counter. On those, or if
+    /// `hz` is below 1kHz, this returns the DWT back as an error.
+    pub fn new(dcb: &mut DCB, mut dwt: DWT, hz: u32) -> Result<Self, DWT> {
+        if hz < 1_000 || !DWT::has_cycle_counter() {
+            return Err(dwt);
+        }
+
+        dcb.enable_trace();
+        // Cortex-M7 locks the DWT registers until they’re unlocked.
+        DWT::unlock();
+        dwt.enable_cycle_counter();
+
+        Ok(DwtCountsMillis {
+            dwt,
+            cycles_per_ms: (u64::from(hz) << 32) / 1_000,
+            last_cycles: AtomicU32::new(DWT::cycle_count()),
+            last_fraction: AtomicU32::new(0),
+            counter: Counter64::new(),
+        })
+    }
+
+    /// Turns this value back into the underlying DWT, disabling the cycle
+    /// counter.
+    pub fn free(mut self) -> DWT {
+        self.dwt.disable_cycle_counter();
+        self.dwt
+    }
+
+    /// Returns the raw cycle count. Wraps every 2³² cycles.
+    pub fn cycles(&self) -> u32 {
+        DWT::cycle_count()
+    }
+
+    /// Counts any whole milliseconds that have passed since the last call, and
+    /// returns the leftover cycles into the current millisecond, as 32.32
+    /// fixed point.
+    fn update(&self) -> u64 {
+        critical::free(|_| {
+            let last = self.last_cycles.load(Ordering::Relaxed);
+            let fraction = self.last_fraction.load(Ordering::Relaxed);
+            let elapsed = DWT::cycle_count().wrapping_sub(last);
+
+            // The last millisecond ended `fraction` of a cycle after `last`,
+            // so the cycle counter may not have quite reached it yet.
+            let elapsed = (u64::from(elapsed) << 32).saturating_sub(u64::from(fraction));
+            // cycles_per_ms is at least 1 << 32, so ms fits in a u32.
+            let ms = elapsed / self.cycles_per_ms;
+
+            if ms > 0 {
+                let advance = ms * self.cycles_per_ms + u64::from(fraction);
+
+                self.last_cycles
+                    .store(last.wrapping_add((advance >> 32) as u32), Ordering::Relaxed);
+                self.last_fraction.store(advance as u32, Ordering::Relaxed);
+                self.counter.add(ms as u32);
+            }
+
+            elapsed - ms * self.cycles_per_ms
+        })
+    }
+}
+
+impl CountsMillis for DwtCountsMillis {
+    /// Returns the number of milliseconds counted so far. Must be called at
+    /// least once every 2³² cycles to keep an accurate count.
+    fn count(&self) -> Wrapping<u32> {
+        self.update();
+        self.counter.get()
+    }
+}
+
+impl CountsMillis64 for DwtCountsMillis {
+    fn count64(&self) -> u64 {
+        self.update();
+        self.counter.get64()
+    }
+}
+
+impl CountsMicros for DwtCountsMillis {
+    /// Combines the millisecond count with the cycles into the current
+    /// millisecond.
+    fn count_micros(&self) -> Wrapping<u32> {
+        critical::free(|_| {
+            let leftover = self.update();
+            // leftover is less than cycles_per_ms, so this is less than 1000.
+            let micros = u128::from(leftover) * 1_000 / u128::from(self.cycles_per_ms);
+
+            self.counter.get() * Wrapping(1_000) + Wrapping(micros as u32)
+        })
+    }
+}
@@ -0,0 +1,302 @@
+//! Implementations of the embedded-hal 0.2 traits, enabled by the (default)
+//! `eh0` feature.
+
+use core::num::Wrapping;
+use core::time::Duration;
+
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::timer::{Cancel, CountDown, Periodic};
+
+use void::Void;
+
+use crate::{
+    has_passed, ignore_not_started, CountDownError, CountsMillis, CountsMillis64, InterruptSysTick,
+    ManualTickCounter, MillisCountDown, MillisCountDown64, PeriodicMillisCountDown, PollingSysTick,
+    SysTickHandle, TimerCountsMillis,
+};
+
+/// Converts a `Duration` to the `u32` of milliseconds that `start_ms` takes,
+/// limiting it to [`MAX_COUNTDOWN_MS`](../constant.MAX_COUNTDOWN_MS.html).
+pub(crate) fn duration_ms(dur: Duration) -> u32 {
+    crate::checked_duration_ms(dur).unwrap_or(crate::MAX_COUNTDOWN_MS)
+}
+
+impl DelayMs<u32> for PollingSysTick {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(self, ms);
+    }
+}
+
+/// Lets one `PollingSysTick` provide delays to several drivers.
+impl DelayMs<u32> for &PollingSysTick {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(*self, ms);
+    }
+}
+
+impl DelayMs<u32> for InterruptSysTick {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(self, ms);
+    }
+}
+
+impl DelayMs<u32> for &InterruptSysTick {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(*self, ms);
+    }
+}
+
+impl DelayMs<u32> for ManualTickCounter {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(self, ms);
+    }
+}
+
+/// Lets a `static` counter be used for delays.
+impl DelayMs<u32> for &ManualTickCounter {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(*self, ms);
+    }
+}
+
+impl<'a> DelayMs<u32> for SysTickHandle<'a> {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(self, ms);
+    }
+}
+
+impl<T: CountDown + Periodic> DelayMs<u32> for TimerCountsMillis<T> {
+    fn delay_ms(&mut self, ms: u32) {
+        crate::delay_ms(self, ms);
+    }
+}
+
+impl DelayUs<u32> for PollingSysTick {
+    /// Busy-waits on SysTick’s current value until `us` microseconds have
+    /// passed.
+    ///
+    /// Resolution is limited by SysTick’s clock. At very low clock speeds a
+    /// microsecond may be less than one tick.
+    fn delay_us(&mut self, us: u32) {
+        crate::delay_us(self, us);
+    }
+}
+
+impl DelayUs<u32> for &PollingSysTick {
+    fn delay_us(&mut self, us: u32) {
+        crate::delay_us(*self, us);
+    }
+}
+
+impl DelayUs<u32> for InterruptSysTick {
+    fn delay_us(&mut self, us: u32) {
+        crate::delay_us(self, us);
+    }
+}
+
+impl DelayUs<u32> for &InterruptSysTick {
+    fn delay_us(&mut self, us: u32) {
+        crate::delay_us(*self, us);
+    }
+}
+
+impl<'a> DelayUs<u32> for SysTickHandle<'a> {
+    fn delay_us(&mut self, us: u32) {
+        crate::delay_us(self, us);
+    }
+}
+
+/// Implements the `u16` and `u8` versions of a delay trait by widening to the
+/// `u32` one, since drivers are often generic over one particular width.
+macro_rules! widen_delay {
+    (impl$([$($generics:tt)*])? $trait:ident::$method:ident for $t:ty) => {
+        impl<$($($generics)*)?> $trait<u16> for $t {
+            fn $method(&mut self, n: u16) {
+                $trait::<u32>::$method(self, u32::from(n));
+            }
+        }
+
+        impl<$($($generics)*)?> $trait<u8> for $t {
+            fn $method(&mut self, n: u8) {
+                $trait::<u32>::$method(self, u32::from(n));
+            }
+        }
+    };
+}
+
+widen_delay!(impl DelayMs::delay_ms for PollingSysTick);
+widen_delay!(impl DelayMs::delay_ms for &PollingSysTick);
+widen_delay!(impl DelayMs::delay_ms for InterruptSysTick);
+widen_delay!(impl DelayMs::delay_ms for &InterruptSysTick);
+widen_delay!(impl DelayMs::delay_ms for ManualTickCounter);
+widen_delay!(impl DelayMs::delay_ms for &ManualTickCounter);
+widen_delay!(impl['a] DelayMs::delay_ms for SysTickHandle<'a>);
+widen_delay!(impl[T: CountDown + Periodic] DelayMs::delay_ms for TimerCountsMillis<T>);
+
+widen_delay!(impl DelayUs::delay_us for PollingSysTick);
+widen_delay!(impl DelayUs::delay_us for &PollingSysTick);
+widen_delay!(impl DelayUs::delay_us for InterruptSysTick);
+widen_delay!(impl DelayUs::delay_us for &InterruptSysTick);
+widen_delay!(impl['a] DelayUs::delay_us for SysTickHandle<'a>);
+
+/// A single countdown built into `PollingSysTick`, for when that’s all you
+/// need. For more than one at a time, make
+/// [`MillisCountDown`](../struct.MillisCountDown.html)s.
+impl CountDown for PollingSysTick {
+    type Time = Duration;
+
+    /// Starts timing the given `Duration`, replacing any countdown already
+    /// running.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.deadline = Some(self.count() + Wrapping(duration_ms(count.into())));
+    }
+
+    /// Returns
+    /// [`nb::Error::WillBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
+    /// while the timer runs, then will return `Result::Ok`.
+    ///
+    /// Calling this method before `start`, or after it has already returned
+    /// `Ok`, returns `Ok` immediately.
+    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
+        match self.deadline {
+            Some(deadline) if !has_passed(self.count(), deadline) => Err(nb::Error::WouldBlock),
+            _ => {
+                self.deadline = None;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Cancel for PollingSysTick {
+    type Error = CountDownError;
+
+    /// Stops the countdown.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown wasn’t running.
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        self.deadline
+            .take()
+            .map(|_| ())
+            .ok_or(CountDownError::NotStarted)
+    }
+}
+
+impl<'a, CM: CountsMillis> CountDown for MillisCountDown<'a, CM> {
+    type Time = Duration;
+
+    /// Starts timing the given `Duration`.
+    ///
+    /// [`wait`](#method.wait) will return
+    /// [`nb::Error::WouldBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
+    /// until this amount of time has passed.
+    ///
+    /// Calling this method before the time has fully ellapsed will reset the
+    /// timer.
+    ///
+    /// Durations longer than
+    /// [`MAX_COUNTDOWN_MS`](../constant.MAX_COUNTDOWN_MS.html) are shortened to
+    /// it. Use [`try_start`](#method.try_start) to get an error instead.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.start_ms(duration_ms(count.into()));
+    }
+
+    /// Returns
+    /// [`nb::Error::WillBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
+    /// while the timer runs, then will return `Result::Ok`.
+    ///
+    /// Calling this method before `start`, or after it has already returned
+    /// `Ok`, returns `Ok` immediately. Use `try_wait_ms` if you need to tell
+    /// the difference.
+    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+}
+
+impl<'a, CM: CountsMillis> Cancel for MillisCountDown<'a, CM> {
+    type Error = CountDownError;
+
+    /// Stops the countdown.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown wasn’t running.
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        MillisCountDown::cancel(self)
+    }
+}
+
+impl<'a, CM: CountsMillis64> CountDown for MillisCountDown64<'a, CM> {
+    type Time = Duration;
+
+    /// Starts timing the given `Duration`.
+    ///
+    /// Calling this method before the time has fully ellapsed will reset the
+    /// timer.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        let dur: Self::Time = count.into();
+        let millis = dur
+            .as_secs()
+            .saturating_mul(1000)
+            .saturating_add(u64::from(dur.subsec_millis()));
+        self.start_ms(millis);
+    }
+
+    /// Returns
+    /// [`nb::Error::WillBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
+    /// while the timer runs, then will return `Result::Ok`.
+    ///
+    /// Calling this method before `start`, or after it has already returned
+    /// `Ok`, returns `Ok` immediately. Use `try_wait_ms` if you need to tell
+    /// the difference.
+    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+}
+
+impl<'a, CM: CountsMillis64> Cancel for MillisCountDown64<'a, CM> {
+    type Error = CountDownError;
+
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        MillisCountDown64::cancel(self)
+    }
+}
+
+impl<'a, CM: CountsMillis> CountDown for PeriodicMillisCountDown<'a, CM> {
+    type Time = Duration;
+
+    /// Starts timing periods of the given `Duration`.
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.start_ms(duration_ms(count.into()));
+    }
+
+    /// Returns
+    /// [`nb::Error::WillBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
+    /// until the current period ends, then returns `Result::Ok` once and starts
+    /// the next period.
+    ///
+    /// Calling this method before `start` returns `Ok` immediately.
+    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+}
+
+impl<'a, CM: CountsMillis> Periodic for PeriodicMillisCountDown<'a, CM> {}
+
+impl<'a, CM: CountsMillis> Cancel for PeriodicMillisCountDown<'a, CM> {
+    type Error = CountDownError;
+
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        PeriodicMillisCountDown::cancel(self)
+    }
+}
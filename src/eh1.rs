@@ -0,0 +1,42 @@
+//! Implementations of the embedded-hal 1.0 traits, enabled by the `eh1`
+//! feature.
+
+use embedded_hal_1::delay::DelayNs;
+
+use crate::{PollingSysTick, SysTickHandle};
+
+/// Implements `DelayNs` for `$t` by busy-waiting on the `$counter` it
+/// dereferences to.
+macro_rules! delay_ns {
+    ($(#[$attr:meta])* impl$([$($generics:tt)*])? for $t:ty => $counter:ty) => {
+        $(#[$attr])*
+        impl<$($($generics)*)?> DelayNs for $t {
+            /// Busy-waits on SysTick’s current value.
+            ///
+            /// Delays are rounded up to the next whole microsecond, since
+            /// that’s the finest resolution that
+            /// [`count_micros`](../trait.CountsMicros.html) provides.
+            fn delay_ns(&mut self, ns: u32) {
+                let counter: &$counter = self;
+                crate::delay_us(counter, ns.div_ceil(1_000));
+            }
+
+            fn delay_us(&mut self, us: u32) {
+                let counter: &$counter = self;
+                crate::delay_us(counter, us);
+            }
+
+            fn delay_ms(&mut self, ms: u32) {
+                let counter: &$counter = self;
+                crate::delay_ms(counter, ms);
+            }
+        }
+    };
+}
+
+delay_ns!(impl for PollingSysTick => PollingSysTick);
+delay_ns!(
+    /// Lets one `PollingSysTick` provide delays to several drivers.
+    impl for &PollingSysTick => PollingSysTick
+);
+delay_ns!(impl['a] for SysTickHandle<'a> => SysTickHandle<'a>);
@@ -0,0 +1,34 @@
+//! embassy-time driver, enabled by the `embassy` feature.
+//!
+//! Enabling this feature registers a time driver for `embassy-time` that reads
+//! the count kept by [`InterruptSysTick`](../struct.InterruptSysTick.html), so
+//! `embassy_time::Timer` and friends will work once you’ve created one and
+//! are calling its `on_interrupt` from the SysTick exception handler.
+//!
+//! The driver ticks once per millisecond, so this feature selects
+//! `embassy-time-driver`’s `tick-hz-1_000` feature. Don’t select a different
+//! tick rate elsewhere in your dependency tree.
+
+use core::task::Waker;
+
+use embassy_time_driver::Driver;
+
+use crate::{interrupt, wakers};
+
+struct SysTickDriver;
+
+impl Driver for SysTickDriver {
+    fn now(&self) -> u64 {
+        interrupt::count64()
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        // If the table is full, or the time has already come, have the
+        // executor poll again right away rather than waiting for SysTick.
+        if !wakers::register(at, waker) || interrupt::count64() >= at {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: SysTickDriver = SysTickDriver);
@@ -0,0 +1,87 @@
+use cortex_m::peripheral::SYST;
+
+#[cfg(feature = "fugit")]
+use crate::CalibrationError;
+use crate::{InterruptSysTick, PollingSysTick, SysTickCalibration};
+
+/// Types that own the SysTick peripheral
+///
+/// This is normally cortex-m’s `SYST`. (cortex-m 0.6.7 re-exports the
+/// peripherals from cortex-m 0.7, so a HAL on either version hands out the
+/// same `SYST`.) If your PAC has its own SysTick type, implement this for it
+/// so that it can be used with [`SysTickExt`](trait.SysTickExt.html) and the
+/// counters’ constructors.
+///
+/// # Safety
+///
+/// Owning a value of the type must mean that nothing else is using SysTick,
+/// just as owning `SYST` does.
+pub unsafe trait SysTickPeripheral: Sized {
+    /// Gives up this value for the `SYST` that the counters take.
+    ///
+    /// For a PAC’s own SysTick type this is usually
+    /// `unsafe { cortex_m::Peripherals::steal().SYST }`, which is sound
+    /// because `self` was the only way to get at SysTick and has been given
+    /// up.
+    fn into_syst(self) -> SYST;
+}
+
+unsafe impl SysTickPeripheral for SYST {
+    fn into_syst(self) -> SYST {
+        self
+    }
+}
+
+/// Extension trait for making the counters straight from the `SYST`
+/// peripheral, in the style of HAL `constrain()` and `split()` methods
+///
+/// It’s implemented for anything that’s a
+/// [`SysTickPeripheral`](trait.SysTickPeripheral.html).
+///
+/// ```ignore
+/// use cortex_m_systick_countdown::SysTickExt;
+///
+/// let systick = core.SYST.countdown_ms(&calibration);
+/// ```
+pub trait SysTickExt {
+    /// Same as [`PollingSysTick::new`](struct.PollingSysTick.html#method.new).
+    fn countdown_ms(self, calibration: &SysTickCalibration) -> PollingSysTick;
+
+    /// Same as [`InterruptSysTick::new`](struct.InterruptSysTick.html#method.new).
+    fn interrupt_ms(self, calibration: &SysTickCalibration) -> InterruptSysTick;
+
+    /// Makes a `PollingSysTick` for SysTick’s clock running at `clock`, such
+    /// as `core.SYST.polling_millis(48.MHz())`.
+    ///
+    /// Returns SysTick back as a `SYST`, along with the reason, if `clock` is
+    /// too slow. (See
+    /// [`SysTickCalibration::from_clock_hz`](struct.SysTickCalibration.html#method.from_clock_hz).)
+    #[cfg(feature = "fugit")]
+    fn polling_millis(
+        self,
+        clock: fugit::HertzU32,
+    ) -> Result<PollingSysTick, (SYST, CalibrationError)>;
+}
+
+impl<P: SysTickPeripheral> SysTickExt for P {
+    fn countdown_ms(self, calibration: &SysTickCalibration) -> PollingSysTick {
+        PollingSysTick::new(self, calibration)
+    }
+
+    fn interrupt_ms(self, calibration: &SysTickCalibration) -> InterruptSysTick {
+        InterruptSysTick::new(self, calibration)
+    }
+
+    #[cfg(feature = "fugit")]
+    fn polling_millis(
+        self,
+        clock: fugit::HertzU32,
+    ) -> Result<PollingSysTick, (SYST, CalibrationError)> {
+        let syst = self.into_syst();
+
+        match SysTickCalibration::from_fugit(clock) {
+            Ok(calibration) => Ok(PollingSysTick::new(syst, &calibration)),
+            Err(err) => Err((syst, err)),
+        }
+    }
+}
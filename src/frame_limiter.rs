@@ -0,0 +1,106 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Never};
+
+/// Paces a display loop to a fixed number of frames per second
+///
+/// Frames are due at even slots measured from when the limiter was created,
+/// even when a second doesn’t divide evenly by `fps`, so the rate doesn’t
+/// drift the way repeated `delay_ms` calls do.
+///
+/// ```ignore
+/// let mut limiter = FrameLimiter::new(&systick, 30);
+///
+/// loop {
+///     draw_frame(&mut display);
+///
+///     let dropped = limiter.wait_for_next_frame();
+///     if dropped > 0 {
+///         log::warn!("dropped {} frames", dropped);
+///     }
+/// }
+/// ```
+///
+/// If a frame takes so long that later slots have already passed, those
+/// frames are dropped rather than drawn back to back, and reported as
+/// overruns.
+pub struct FrameLimiter<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    fps: u32,
+    /// Start of the current second of frame slots
+    second_start: Wrapping<u32>,
+    /// Index within the current second of the next frame slot
+    frame: u32,
+    overruns: u32,
+}
+
+impl<'a, CM: CountsMillis> FrameLimiter<'a, CM> {
+    /// Creates a `FrameLimiter` for `fps` frames per second, with the first
+    /// frame slot one frame from now. An `fps` of 0 is treated as 1.
+    pub fn new(counter: &'a CM, fps: u32) -> Self {
+        FrameLimiter {
+            counter,
+            fps: fps.max(1),
+            second_start: counter.count(),
+            frame: 1,
+            overruns: 0,
+        }
+    }
+
+    /// Returns `Ok` once the next frame slot has arrived, or
+    /// `nb::Error::WouldBlock` until then.
+    ///
+    /// The `Ok` value is how many frame slots were missed since the last one,
+    /// which is 0 unless the previous frame overran.
+    pub fn poll_next_frame(&mut self) -> nb::Result<u32, Never> {
+        let now = self.counter.count();
+
+        if !has_passed(now, self.slot_millis(self.frame)) {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        // Slot k is at k * 1000 / fps, which has passed once
+        // k * 1000 < elapsed * fps.
+        let elapsed = u64::from((now - self.second_start).0);
+        let last_passed = ((elapsed * u64::from(self.fps) - 1) / 1000) as u32;
+        let missed = last_passed - self.frame;
+
+        self.overruns = self.overruns.saturating_add(missed);
+
+        // Keep frame small by moving the start along a whole second at a time.
+        let next = last_passed + 1;
+        let seconds = next / self.fps;
+        self.second_start += Wrapping(seconds.wrapping_mul(1000));
+        self.frame = next % self.fps;
+
+        Ok(missed)
+    }
+
+    /// Busy-waits for the next frame slot, returning how many slots were
+    /// missed.
+    pub fn wait_for_next_frame(&mut self) -> u32 {
+        nb::block!(self.poll_next_frame()).unwrap()
+    }
+
+    /// Returns the total number of frame slots missed so far.
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Returns the frame rate.
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    /// Starts the frame slots over, with the next one a frame from now.
+    pub fn reset(&mut self) {
+        self.second_start = self.counter.count();
+        self.frame = 1;
+        self.overruns = 0;
+    }
+
+    fn slot_millis(&self, frame: u32) -> Wrapping<u32> {
+        let offset = u64::from(frame) * 1000 / u64::from(self.fps);
+        self.second_start + Wrapping(offset as u32)
+    }
+}
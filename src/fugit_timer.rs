@@ -0,0 +1,75 @@
+//! Implementations of fugit-timer’s traits, enabled by the `fugit-timer`
+//! feature.
+
+use fugit_timer::{Delay, Timer, TimerDurationU32, TimerInstantU32};
+
+use crate::{
+    CountDownError, CountsMillis, InterruptSysTick, ManualTickCounter, MillisCountDown, Never,
+    PollingSysTick, MAX_COUNTDOWN_MS,
+};
+
+impl<'a, CM: CountsMillis> Timer<1_000> for MillisCountDown<'a, CM> {
+    type Error = CountDownError;
+
+    fn now(&mut self) -> TimerInstantU32<1_000> {
+        TimerInstantU32::from_ticks(self.counter.count().0)
+    }
+
+    /// Returns `CountDownError::TooLong` if `duration` is longer than
+    /// [`MAX_COUNTDOWN_MS`](constant.MAX_COUNTDOWN_MS.html).
+    fn start(&mut self, duration: TimerDurationU32<1_000>) -> Result<(), CountDownError> {
+        let ms = duration.ticks();
+
+        if ms > MAX_COUNTDOWN_MS {
+            return Err(CountDownError::TooLong);
+        }
+
+        self.start_ms(ms);
+        Ok(())
+    }
+
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        MillisCountDown::cancel(self)
+    }
+
+    /// Same as [`try_wait_ms`](#method.try_wait_ms).
+    fn wait(&mut self) -> nb::Result<(), CountDownError> {
+        self.try_wait_ms()
+    }
+}
+
+impl Delay<1_000> for PollingSysTick {
+    type Error = Never;
+
+    fn delay(&mut self, duration: TimerDurationU32<1_000>) -> Result<(), Never> {
+        crate::delay_ms(self, duration.ticks());
+        Ok(())
+    }
+}
+
+impl Delay<1_000> for InterruptSysTick {
+    type Error = Never;
+
+    fn delay(&mut self, duration: TimerDurationU32<1_000>) -> Result<(), Never> {
+        crate::delay_ms(self, duration.ticks());
+        Ok(())
+    }
+}
+
+impl Delay<1_000> for ManualTickCounter {
+    type Error = Never;
+
+    fn delay(&mut self, duration: TimerDurationU32<1_000>) -> Result<(), Never> {
+        crate::delay_ms(self, duration.ticks());
+        Ok(())
+    }
+}
+
+impl Delay<1_000> for &ManualTickCounter {
+    type Error = Never;
+
+    fn delay(&mut self, duration: TimerDurationU32<1_000>) -> Result<(), Never> {
+        crate::delay_ms(*self, duration.ticks());
+        Ok(())
+    }
+}
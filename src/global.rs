@@ -0,0 +1,90 @@
+//! Arduino-style global time functions, enabled by the `global` feature.
+//!
+//! [`install`](fn.install.html) a `PollingSysTick` or `InterruptSysTick` once
+//! at startup, and then [`millis`](fn.millis.html) and
+//! [`micros`](fn.micros.html) can be called from anywhere, including interrupt
+//! handlers, without passing references around.
+//!
+//! Both functions briefly enter a critical section to get at the installed
+//! counter.
+
+use core::cell::RefCell;
+
+use crate::critical::{self, Mutex};
+
+use crate::{CountsMicros, CountsMillis, InterruptSysTick, PollingSysTick};
+
+/// A counter that can be installed as the global time source.
+///
+/// You normally won’t need to name this: `install` takes anything that can be
+/// converted into one, and `uninstall` returns it so you can get your counter
+/// back out.
+pub enum GlobalSysTick {
+    /// An installed [`PollingSysTick`](../struct.PollingSysTick.html).
+    Polling(PollingSysTick),
+    /// An installed [`InterruptSysTick`](../struct.InterruptSysTick.html).
+    Interrupt(InterruptSysTick),
+}
+
+impl From<PollingSysTick> for GlobalSysTick {
+    fn from(systick: PollingSysTick) -> Self {
+        GlobalSysTick::Polling(systick)
+    }
+}
+
+impl From<InterruptSysTick> for GlobalSysTick {
+    fn from(systick: InterruptSysTick) -> Self {
+        GlobalSysTick::Interrupt(systick)
+    }
+}
+
+static GLOBAL: Mutex<RefCell<Option<GlobalSysTick>>> = Mutex::new(RefCell::new(None));
+
+/// Makes `systick` the source for `millis` and `micros`.
+///
+/// If a counter is already installed, returns `systick` back as an error.
+pub fn install<S: Into<GlobalSysTick>>(systick: S) -> Result<(), GlobalSysTick> {
+    let systick = systick.into();
+
+    critical::free(|cs| {
+        let mut global = GLOBAL.borrow(cs).borrow_mut();
+
+        if global.is_some() {
+            Err(systick)
+        } else {
+            *global = Some(systick);
+            Ok(())
+        }
+    })
+}
+
+/// Removes and returns the installed counter, if there is one.
+///
+/// Afterwards `millis` and `micros` will return 0 until another counter is
+/// installed.
+pub fn uninstall() -> Option<GlobalSysTick> {
+    critical::free(|cs| GLOBAL.borrow(cs).borrow_mut().take())
+}
+
+/// Returns the number of milliseconds counted by the installed counter, or 0
+/// if there isn’t one. Wraps after about 49.7 days.
+///
+/// If a `PollingSysTick` is installed, this has the same requirement as its
+/// `count` method that it be called at least once per millisecond.
+pub fn millis() -> u32 {
+    critical::free(|cs| match &*GLOBAL.borrow(cs).borrow() {
+        Some(GlobalSysTick::Polling(systick)) => systick.count().0,
+        Some(GlobalSysTick::Interrupt(systick)) => systick.count().0,
+        None => 0,
+    })
+}
+
+/// Returns the number of microseconds counted by the installed counter, or 0
+/// if there isn’t one. Wraps after about 71.6 minutes.
+pub fn micros() -> u32 {
+    critical::free(|cs| match &*GLOBAL.borrow(cs).borrow() {
+        Some(GlobalSysTick::Polling(systick)) => systick.count_micros().0,
+        Some(GlobalSysTick::Interrupt(systick)) => systick.count_micros().0,
+        None => 0,
+    })
+}
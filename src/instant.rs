@@ -0,0 +1,99 @@
+use core::cmp::Ordering;
+use core::num::Wrapping;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
+
+use crate::CountsMillis;
+
+/// A point in time, as counted by a [`CountsMillis`](trait.CountsMillis.html)
+/// source.
+///
+/// Since the count wraps around every 49.7 days, `Instant`s are compared in a
+/// rollover-safe way: one `Instant` is considered later than another if it’s
+/// less than about 24.8 days (`i32::MAX` milliseconds) ahead of it. This means
+/// that comparisons are only meaningful between `Instant`s that are within
+/// that range of each other. `Instant`s exactly 2^31 milliseconds apart can’t
+/// be put in order, so they aren’t less than, greater than, or equal to each
+/// other, and `Instant` implements `PartialOrd` but not `Ord`.
+///
+/// Adding and subtracting `u32`s moves an `Instant` by that many
+/// milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instant(Wrapping<u32>);
+
+impl Instant {
+    /// Returns the current time from `counter`.
+    pub fn now<CM: CountsMillis>(counter: &CM) -> Self {
+        Instant(counter.count())
+    }
+
+    /// Creates an `Instant` from a raw millisecond count, as returned by
+    /// [`CountsMillis::count`](trait.CountsMillis.html#tymethod.count).
+    pub fn from_millis(millis: u32) -> Self {
+        Instant(Wrapping(millis))
+    }
+
+    /// Returns the raw millisecond count for this `Instant`.
+    pub fn millis(self) -> u32 {
+        (self.0).0
+    }
+
+    /// Returns how many milliseconds after `earlier` this `Instant` is, or
+    /// `None` if `earlier` is actually later.
+    pub fn checked_millis_since(self, earlier: Instant) -> Option<u32> {
+        let diff = (self.0 - earlier.0).0;
+
+        if diff as i32 >= 0 {
+            Some(diff)
+        } else {
+            None
+        }
+    }
+
+    /// Returns how long after `earlier` this `Instant` is, or `None` if
+    /// `earlier` is actually later.
+    pub fn checked_duration_since(self, earlier: Instant) -> Option<Duration> {
+        self.checked_millis_since(earlier)
+            .map(|ms| Duration::from_millis(u64::from(ms)))
+    }
+}
+
+// Only `PartialOrd`, since the rollover-safe comparison isn’t a total order:
+// it goes around in a circle, and two `Instant`s exactly 2^31 ms apart are
+// each as far ahead of the other as behind it.
+impl PartialOrd for Instant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.0 - other.0).0 as i32 {
+            i32::MIN => None,
+            diff => Some(diff.cmp(&0)),
+        }
+    }
+}
+
+impl Add<u32> for Instant {
+    type Output = Instant;
+
+    fn add(self, ms: u32) -> Instant {
+        Instant(self.0 + Wrapping(ms))
+    }
+}
+
+impl AddAssign<u32> for Instant {
+    fn add_assign(&mut self, ms: u32) {
+        self.0 += Wrapping(ms);
+    }
+}
+
+impl Sub<u32> for Instant {
+    type Output = Instant;
+
+    fn sub(self, ms: u32) -> Instant {
+        Instant(self.0 - Wrapping(ms))
+    }
+}
+
+impl SubAssign<u32> for Instant {
+    fn sub_assign(&mut self, ms: u32) {
+        self.0 -= Wrapping(ms);
+    }
+}
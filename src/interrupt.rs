@@ -0,0 +1,292 @@
+use core::cell::Cell;
+use core::num::Wrapping;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m::peripheral::{scb::SystemHandler, syst::SystClkSource, SCB, SYST};
+
+use crate::builder::InterruptSysTickBuilder;
+use crate::counter::Counter64;
+use crate::critical::{self, Mutex};
+use crate::reload::ReloadDither;
+use crate::{
+    micros_from_current, sleep_ms, syst_has_wrapped, CountsMicros, CountsMillis, CountsMillis64,
+    Instant, SysTickCalibration, SysTickPeripheral,
+};
+
+/// Milliseconds counted by the SysTick exception handler.
+///
+/// There is only one SysTick, so there only needs to be one of these. The
+/// exception handler is its only writer, and it can’t preempt itself, so it
+/// can increment this without a critical section (even on thumbv6, which has
+/// no atomic read-modify-write instructions).
+static COUNTER: Counter64 = Counter64::new();
+
+/// Set while an `InterruptSysTick` owns SysTick.
+///
+/// Reading SysTick’s CSR clears COUNTFLAG, so `on_interrupt` only looks at it
+/// while this is set. Otherwise a `PollingSysTick` with its exception enabled
+/// would lose its wraps to the handler.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// When an alarm goes off, and what it calls.
+type Alarm = (Instant, fn());
+
+/// The alarm set by `set_alarm`, if it hasn’t gone off yet.
+static ALARM: Mutex<Cell<Option<Alarm>>> = Mutex::new(Cell::new(None));
+
+/// Adjusts SysTick’s period for calibrations that aren’t a whole number of
+/// ticks.
+static DITHER: ReloadDither = ReloadDither::new();
+
+/// Millisecond counter based on the SysTick exception
+///
+/// Like [`PollingSysTick`](struct.PollingSysTick.html), this consumes the only
+/// SYST value in the program. (Use [`free`](#method.free) if you need to get it
+/// back.)
+///
+/// ## Usage
+///
+/// `InterruptSysTick` enables the SysTick exception, but unless the `rt`
+/// feature is on, it doesn’t define the handler for you. Your program needs to
+/// call [`on_interrupt`](#method.on_interrupt) from it:
+///
+/// ```ignore
+/// #[exception]
+/// fn SysTick() {
+///     InterruptSysTick::on_interrupt();
+/// }
+/// ```
+///
+/// To set the exception’s priority before it starts firing, or the count to
+/// start from, use [`builder`](#method.builder).
+///
+/// After that, use it just like `PollingSysTick`: as a
+/// [`DelayMs`](https://docs.rs/embedded-hal/0.2.3/embedded_hal/blocking/delay/trait.DelayMs.html)
+/// or as the source for [`MillisCountDown`](struct.MillisCountDown.html)
+/// instances.
+///
+/// ## Implementation
+///
+/// Since the count is advanced by the exception handler, time keeps passing
+/// even if nothing calls [`count`](#method.count) for a long while. The flip
+/// side is that the count stops advancing while interrupts are disabled, so
+/// use `PollingSysTick` if you need to time things inside critical sections.
+pub struct InterruptSysTick {
+    syst: SYST,
+}
+
+impl InterruptSysTick {
+    /// Configures SysTick based on the values provided in the calibration and
+    /// enables its exception.
+    ///
+    /// Resets the count to 0.
+    pub fn new<P: SysTickPeripheral>(syst: P, calibration: &SysTickCalibration) -> Self {
+        InterruptSysTick::builder().build(syst, calibration)
+    }
+
+    /// Starts configuring an `InterruptSysTick` with more options than `new`.
+    pub fn builder() -> InterruptSysTickBuilder {
+        InterruptSysTickBuilder::new()
+    }
+
+    /// Like [`new`](#method.new), but sets the count to `ms`, such as time
+    /// saved from before a reset. Also handy for testing how your timeouts
+    /// cope with the `u32` count wrapping, by starting a few milliseconds
+    /// before it does.
+    pub fn starting_at<P: SysTickPeripheral>(
+        syst: P,
+        calibration: &SysTickCalibration,
+        ms: u64,
+    ) -> Self {
+        InterruptSysTick::builder()
+            .initial_count(ms)
+            .build(syst, calibration)
+    }
+
+    pub(crate) fn configure(
+        mut syst: SYST,
+        calibration: &SysTickCalibration,
+        settings: &InterruptSysTickBuilder,
+    ) -> Self {
+        syst.disable_counter();
+        syst.disable_interrupt();
+        syst.set_clock_source(SystClkSource::Core);
+        DITHER.set(calibration.reload_for_ms());
+        syst.clear_current();
+        DITHER.restart();
+
+        COUNTER.set(settings.initial_count);
+
+        if let Some(priority) = settings.priority {
+            // Safety: `InterruptSysTickBuilder::priority` is unsafe, and its
+            // caller promised that nothing else is changing priorities.
+            unsafe {
+                cortex_m::Peripherals::steal()
+                    .SCB
+                    .set_priority(SystemHandler::SysTick, priority);
+            }
+        }
+
+        #[cfg(feature = "rtic")]
+        crate::rtic::initialize();
+
+        INSTALLED.store(true, Ordering::Relaxed);
+        syst.enable_interrupt();
+        syst.enable_counter();
+
+        InterruptSysTick { syst }
+    }
+
+    /// Changes the SysTick exception’s priority while it’s running.
+    ///
+    /// Prefer setting it with
+    /// [`InterruptSysTickBuilder::priority`](struct.InterruptSysTickBuilder.html#method.priority),
+    /// so that the exception never fires at the wrong priority. This is for
+    /// changing it afterwards.
+    ///
+    /// ```ignore
+    /// let mut systick = InterruptSysTick::new(cp.SYST, &calibration);
+    /// unsafe { systick.set_priority(&mut cp.SCB, 0x80) };
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Like `SCB::set_priority`, changing priorities can break priority-based
+    /// critical sections, such as RTIC’s resource locks.
+    pub unsafe fn set_priority(&mut self, scb: &mut SCB, priority: u8) {
+        scb.set_priority(SystemHandler::SysTick, priority);
+    }
+
+    /// Returns the SysTick exception’s priority, as set by the builder or
+    /// [`set_priority`](#method.set_priority).
+    pub fn priority(&self) -> u8 {
+        SCB::get_priority(SystemHandler::SysTick)
+    }
+
+    /// Turns this value back into the underlying SysTick, disabling its
+    /// exception.
+    pub fn free(mut self) -> SYST {
+        self.syst.disable_interrupt();
+        INSTALLED.store(false, Ordering::Relaxed);
+        self.syst
+    }
+
+    /// Blocks for `ms` milliseconds, sleeping with `wfi` between ticks instead
+    /// of polling at full power.
+    ///
+    /// Interrupts must be enabled, since the SysTick exception is what wakes
+    /// the processor.
+    pub fn delay_ms_low_power(&mut self, ms: u32) {
+        sleep_ms(self, ms);
+    }
+
+    /// Calls `callback` from the SysTick exception once the count reaches
+    /// `at`, replacing any alarm that was already set.
+    ///
+    /// If `at` has already passed, `callback` is called on the next tick.
+    /// Since it runs in the exception handler, it should be quick, and
+    /// anything it shares with the rest of the program needs the usual
+    /// interrupt-safe handling. It’s a plain `fn`, so it can’t capture
+    /// anything: keep its state in a `static`.
+    ///
+    /// ```ignore
+    /// fn on_alarm() {
+    ///     LED_OFF.store(true, Ordering::Relaxed);
+    /// }
+    ///
+    /// systick.set_alarm(Instant::now(&systick) + 500, on_alarm);
+    /// ```
+    pub fn set_alarm(&self, at: Instant, callback: fn()) {
+        critical::free(|cs| ALARM.borrow(cs).set(Some((at, callback))));
+    }
+
+    /// Cancels the alarm from [`set_alarm`](#method.set_alarm). Returns false
+    /// if there wasn’t one, or it had already gone off.
+    pub fn clear_alarm(&self) -> bool {
+        critical::free(|cs| ALARM.borrow(cs).take().is_some())
+    }
+
+    /// Advances the count by one millisecond.
+    ///
+    /// This must only be called from your program’s SysTick exception handler.
+    /// It does nothing unless an `InterruptSysTick` exists, so it leaves SysTick
+    /// alone while a `PollingSysTick` is using it.
+    pub fn on_interrupt() {
+        if !INSTALLED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // The exception can also be pended by software (RTIC does this to
+        // check its queue), so only count it if SysTick actually wrapped.
+        if syst_has_wrapped() {
+            COUNTER.increment();
+            DITHER.on_wrap();
+        }
+
+        let now = now();
+        let alarm = critical::free(|cs| {
+            let alarm = ALARM.borrow(cs);
+
+            match alarm.get() {
+                Some((at, callback)) if now >= at => {
+                    alarm.set(None);
+                    Some(callback)
+                }
+                _ => None,
+            }
+        });
+
+        if let Some(callback) = alarm {
+            callback();
+        }
+
+        #[cfg(any(feature = "async", feature = "embassy"))]
+        crate::wakers::wake_expired(COUNTER.get64());
+
+        #[cfg(feature = "rtic")]
+        crate::rtic::on_interrupt();
+    }
+}
+
+/// Reads the count without needing an `InterruptSysTick` reference, for
+/// `Alarms::on_interrupt`.
+pub(crate) fn now() -> Instant {
+    Instant::from_millis(COUNTER.get().0)
+}
+
+/// Reads the 64-bit count without needing an `InterruptSysTick` reference.
+#[cfg(any(feature = "embassy", feature = "rtic", feature = "rtic1"))]
+pub(crate) fn count64() -> u64 {
+    COUNTER.get64()
+}
+
+/// Sets the count back to 0, for RTIC 1’s `Monotonic::reset`.
+#[cfg(feature = "rtic1")]
+pub(crate) fn reset_count() {
+    COUNTER.set(0);
+}
+
+impl CountsMillis for InterruptSysTick {
+    /// Returns the number of milliseconds that the SysTick exception has
+    /// counted.
+    fn count(&self) -> Wrapping<u32> {
+        COUNTER.get()
+    }
+}
+
+impl CountsMillis64 for InterruptSysTick {
+    fn count64(&self) -> u64 {
+        COUNTER.get64()
+    }
+}
+
+impl CountsMicros for InterruptSysTick {
+    /// Combines the millisecond count with how far SysTick has gotten through
+    /// the current millisecond.
+    ///
+    /// Like the millisecond count, this will lag behind while interrupts are
+    /// disabled, since the SysTick exception can’t run to count wraps.
+    fn count_micros(&self) -> Wrapping<u32> {
+        micros_from_current(|| COUNTER.get(), || DITHER.running_reload(), 1)
+    }
+}
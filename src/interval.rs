@@ -0,0 +1,61 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Never};
+
+/// Source of ticks every `period_ms` milliseconds
+///
+/// Poll [`poll_tick`](#method.poll_tick), which returns `Ok` once per period,
+/// instead of waiting on a countdown and remembering to restart it each time:
+///
+/// ```ignore
+/// let mut interval = Interval::every_ms(&systick, 10);
+///
+/// loop {
+///     nb::block!(interval.poll_tick()).unwrap();
+///     sample_sensor();
+/// }
+/// ```
+///
+/// With the `async` feature, an `Interval` on a
+/// [`CountsMillis64`](trait.CountsMillis64.html) counter is also a
+/// `futures_core::Stream` that yields `()` each period.
+///
+/// Each tick is timed from when the previous one was due, so sampling happens
+/// at a fixed rate without drifting. If polling falls behind, `poll_tick`
+/// returns `Ok` right away for each missed tick until it catches up.
+pub struct Interval<'a, CM: CountsMillis> {
+    pub(crate) counter: &'a CM,
+    period_ms: u32,
+    pub(crate) next_tick: Wrapping<u32>,
+}
+
+impl<'a, CM: CountsMillis> Interval<'a, CM> {
+    /// Creates an `Interval` whose first tick is `period_ms` from now.
+    pub fn every_ms(counter: &'a CM, period_ms: u32) -> Self {
+        Interval {
+            counter,
+            period_ms,
+            next_tick: counter.count() + Wrapping(period_ms),
+        }
+    }
+
+    /// Returns `Ok` if a tick is due, or `nb::Error::WouldBlock` if not.
+    pub fn poll_tick(&mut self) -> nb::Result<(), Never> {
+        if has_passed(self.counter.count(), self.next_tick) {
+            self.next_tick += Wrapping(self.period_ms);
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Returns the period between ticks.
+    pub fn period_ms(&self) -> u32 {
+        self.period_ms
+    }
+
+    /// Starts the ticks over, with the next one `period_ms` from now.
+    pub fn reset(&mut self) {
+        self.next_tick = self.counter.count() + Wrapping(self.period_ms);
+    }
+}
@@ -0,0 +1,37 @@
+use core::iter::FusedIterator;
+
+use crate::{CountsMillis, MillisCountDown};
+
+/// Iterator that keeps going until a deadline passes
+///
+/// Returned by
+/// [`CountsMillis::iter_until_ms`](trait.CountsMillis.html#method.iter_until_ms).
+/// Each item is the number of milliseconds since it was created.
+pub struct IterUntil<'a, CM: CountsMillis> {
+    count_down: MillisCountDown<'a, CM>,
+}
+
+impl<'a, CM: CountsMillis> IterUntil<'a, CM> {
+    pub(crate) fn new(counter: &'a CM, ms: u32) -> Self {
+        let mut count_down = MillisCountDown::new(counter);
+        count_down.start_ms(ms);
+
+        IterUntil { count_down }
+    }
+}
+
+impl<'a, CM: CountsMillis> Iterator for IterUntil<'a, CM> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.count_down.wait_ms().is_ok() {
+            None
+        } else {
+            self.count_down.elapsed_ms()
+        }
+    }
+}
+
+// Once the deadline passes, the countdown stops, and so `wait_ms` keeps
+// returning `Ok`.
+impl<'a, CM: CountsMillis> FusedIterator for IterUntil<'a, CM> {}
@@ -0,0 +1,73 @@
+use cortex_m::peripheral::SYST;
+
+use crate::SysTickCalibration;
+
+/// Reading of SysTick’s current value register, for timing short intervals
+/// such as interrupt latency to the tick
+///
+/// Take one snapshot where the interval starts, such as just before pending an
+/// interrupt or in the code that triggers a peripheral, and another where it
+/// ends, such as at the top of the interrupt handler. Then
+/// [`nanos_since`](#method.nanos_since) says how long was in between, without
+/// needing a scope:
+///
+/// ```ignore
+/// static TRIGGERED: Mutex<Cell<Option<SysTickSnapshot>>> = ...;
+///
+/// TRIGGERED.borrow(cs).set(Some(SysTickSnapshot::take()));
+/// NVIC::pend(Interrupt::EXTI0);
+///
+/// #[interrupt]
+/// fn EXTI0() {
+///     let entry = SysTickSnapshot::take();
+///     let triggered = free(|cs| TRIGGERED.borrow(cs).get()).unwrap();
+///     record_latency(entry.nanos_since(triggered, &calibration));
+/// }
+/// ```
+///
+/// SysTick must be running, with any of this crate’s counters or on its own.
+/// Its value counts down and starts over each period (a millisecond, for the
+/// counters here), so two snapshots are only comparable if they’re less than
+/// a period apart. Wrapping once in between is allowed for; wrapping more than
+/// once can’t be detected, and the result will come out short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SysTickSnapshot {
+    current: u32,
+    reload: u32,
+}
+
+impl SysTickSnapshot {
+    /// Reads SysTick’s current value. Reading it doesn’t disturb the
+    /// counters.
+    pub fn take() -> Self {
+        SysTickSnapshot {
+            current: SYST::get_current(),
+            reload: SYST::get_reload(),
+        }
+    }
+
+    /// Returns the raw value from SysTick’s current value register.
+    pub fn current(self) -> u32 {
+        self.current
+    }
+
+    /// Returns how many SysTick ticks passed between `earlier` and this
+    /// snapshot.
+    pub fn ticks_since(self, earlier: SysTickSnapshot) -> u32 {
+        if earlier.current >= self.current {
+            earlier.current - self.current
+        } else {
+            // SysTick reloaded in between. It went from `earlier.current` down
+            // to 0, then from the reload value down to `self.current`, and
+            // the reload itself takes a tick.
+            earlier.current + (earlier.reload + 1 - self.current)
+        }
+    }
+
+    /// Returns how many nanoseconds passed between `earlier` and this
+    /// snapshot, with SysTick running at `calibration`’s clock rate.
+    pub fn nanos_since(self, earlier: SysTickSnapshot, calibration: &SysTickCalibration) -> u64 {
+        calibration.ticks_to_nanos(self.ticks_since(earlier))
+    }
+}
@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(clippy::all)]
 
 //! Wrappers around the Cortex-M SysTick peripheral for making
@@ -12,37 +12,454 @@
 //!
 //! Create an instance of [`PollingSysTick`](struct.PollingSysTick.html) after
 //! you have configured your clocks. It consumes the `SYST` peripheral in order
-//! to get exclusive control over it.
+//! to get exclusive control over it. Its
+//! [`builder`](struct.PollingSysTick.html#method.builder) has options for the
+//! clock source, tick length, and more. [`SysTickExt`](trait.SysTickExt.html)
+//! lets you make one straight from `SYST` instead.
 //!
 //! You can use the [`embedded_hal::blocking::delay::DelayMs`
 //! trait](https://docs.rs/embedded-hal/0.2.3/embedded_hal/blocking/delay/trait.DelayMs.html)
-//! on `PollingSysTick` directly, or you can use `PollingSysTick` to make
-//! `MillisCountDown` instances that are independent, non-blocking
-//! counters.
+//! (or `DelayUs`, for shorter delays) on `PollingSysTick` directly, or on
+//! `&PollingSysTick` to share it among drivers. You can also use
+//! `PollingSysTick` to make `MillisCountDown` instances that are independent,
+//! non-blocking counters. (If you only need one, `PollingSysTick` implements
+//! `CountDown` itself.) To give several drivers their own delay provider
+//! from the one SysTick, wrap it in a
+//! [`SharedSysTick`](struct.SharedSysTick.html). For drivers that can’t hold
+//! on to a borrow of the counter, a
+//! [`CountDownPool`](struct.CountDownPool.html) can own it and hand out
+//! countdowns as plain handles, or a
+//! [`CounterCell`](struct.CounterCell.html) can keep it in a `static` so that
+//! its countdowns don’t need a lifetime.
+//!
+//! If you’d rather have SysTick count via its exception than by polling, use
+//! [`InterruptSysTick`](struct.InterruptSysTick.html) instead. If an RTOS or
+//! your HAL already owns SysTick, use
+//! [`ManualTickCounter`](struct.ManualTickCounter.html) and advance it from
+//! their tick hook. On cores with a DWT cycle counter (anything but Cortex-M0
+//! and M0+), [`DwtCountsMillis`](struct.DwtCountsMillis.html) leaves SysTick
+//! alone entirely. With the `eh0` feature,
+//! [`TimerCountsMillis`](struct.TimerCountsMillis.html) can count using any
+//! periodic embedded-hal `CountDown` timer from your HAL. Panic and fault
+//! handlers that can’t rely on any of these can call
+//! [`panic_delay_ms`](fn.panic_delay_ms.html).
+//!
+//! To juggle many timeouts at once, such as in a protocol stack, use a
+//! [`TimerQueue`](struct.TimerQueue.html) instead of separate
+//! `MillisCountDown`s. To run functions at fixed intervals from your main
+//! loop, use a [`Scheduler`](struct.Scheduler.html), and to call one once
+//! after a while, an [`Alarms`](struct.Alarms.html). To drive a control
+//! loop at a rate that doesn’t drift, use a
+//! [`Metronome`](struct.Metronome.html). For display loops there’s also
+//! [`FrameLimiter`](struct.FrameLimiter.html). To give up on an `nb`
+//! operation that takes too long, use
+//! [`with_timeout_ms`](fn.with_timeout_ms.html), or to try it again a few
+//! times, [`retry_ms`](fn.retry_ms.html). To wait longer and longer between
+//! retries, use [`Backoff`](struct.Backoff.html). For handshakes with several
+//! steps, each with its own timeout, use a
+//! [`TimedFsm`](struct.TimedFsm.html). With the `eh0` feature,
+//! [`TimedSerial`](struct.TimedSerial.html) puts timeouts on reads from a
+//! serial port, [`TimedSpi`](struct.TimedSpi.html) does the same for SPI
+//! transfers, and [`recover_i2c_bus`](fn.recover_i2c_bus.html) gets a stuck
+//! I2C bus going again.
+//!
+//! To measure how long things take, use a
+//! [`Stopwatch`](struct.Stopwatch.html) or, for microsecond statistics, a
+//! [`Profiler`](struct.Profiler.html). For intervals shorter than a
+//! millisecond, such as interrupt latency, compare two
+//! [`SysTickSnapshot`](struct.SysTickSnapshot.html)s. To measure how often
+//! events happen, use a [`RateMeter`](struct.RateMeter.html), and to limit
+//! how often something happens, use a [`Throttle`](struct.Throttle.html). To
+//! read buttons and switches without their contacts bouncing, use
+//! [`Debouncer`](struct.Debouncer.html). To notice when a loop or task has
+//! stalled, use a [`SoftWatchdog`](struct.SoftWatchdog.html). For slow PWM on
+//! pins without a timer channel, use [`SoftPwm`](struct.SoftPwm.html). To
+//! pulse an output for a while after something happens, such as a buzzer
+//! chirp, use a [`Monostable`](struct.Monostable.html), and to blink a
+//! status LED in a pattern, a [`BlinkPattern`](struct.BlinkPattern.html).
+//! For fades and soft-starts, a [`Ramp`](struct.Ramp.html) works out how far
+//! along a value should be.
+//!
+//! Once you know the actual time of day from GPS, a host computer, or
+//! similar, [`WallClock`](struct.WallClock.html) can keep track of it on top of
+//! any of these counters.
+//!
+//! ## Features
+//!
+//! * `eh0` (default): implementations of the embedded-hal 0.2 `CountDown`,
+//!   `DelayMs`, and `DelayUs` traits. For drivers that want a `CountDown`
+//!   whose `Time` isn’t `Duration`, see
+//!   [`TypedMillisCountDown`](struct.TypedMillisCountDown.html).
+//! * `eh1`: implementation of the embedded-hal 1.0 `DelayNs` trait.
+//! * `async`: implementation of the embedded-hal-async `DelayNs` trait for
+//!   `InterruptSysTick`, and the [`TimerFuture`](struct.TimerFuture.html) and
+//!   [`Ticker`](struct.Ticker.html) types for hand-rolled executors. Also
+//!   makes [`Interval`](struct.Interval.html) a `futures_core::Stream`.
+//! * `rt`: defines the SysTick exception handler for `InterruptSysTick` with
+//!   cortex-m-rt, so you don’t have to. Use
+//!   [`set_tick_hook`](fn.set_tick_hook.html) to run your own code from it as
+//!   well. Don’t turn this on if something else, like an RTIC 1 monotonic,
+//!   defines the handler. The handler only touches SysTick while an
+//!   `InterruptSysTick` exists, so a `PollingSysTick` with its exception
+//!   enabled keeps counting its own wraps.
+//! * `rtic`: an RTIC 2 monotonic, [`rtic::SysTickMono`](rtic/struct.SysTickMono.html),
+//!   driven by `InterruptSysTick`.
+//! * `rtic1`: an RTIC 1 monotonic (`rtic-monotonic` 1.0),
+//!   [`rtic1::SysTickMonotonic`](rtic1/struct.SysTickMonotonic.html), driven
+//!   by `InterruptSysTick`.
+//! * `embassy`: an `embassy-time` driver driven by `InterruptSysTick`. Ticks at
+//!   1kHz.
+//! * `defmt`: implementations of defmt’s `Format` trait for `Instant`,
+//!   `MillisCountDown`, the error types, and other plain data.
+//! * `defmt-timestamp`: timestamps defmt log messages with
+//!   [`global::millis`](global/fn.millis.html). Turns on `defmt` and `global`.
+//! * `global`: Arduino-style [`global::millis`](global/fn.millis.html) and
+//!   [`global::micros`](global/fn.micros.html) functions.
+//! * `fugit`: methods for starting `MillisCountDown`s with fugit durations,
+//!   reading `CountsMillis` values as fugit instants, and making
+//!   `SysTickCalibration`s from fugit rates.
+//! * `fugit-timer`: implementations of fugit-timer’s `Timer` trait for
+//!   `MillisCountDown` and its `Delay` trait for the SysTick counters.
+//! * `embedded-time`: implementations of embedded-time’s `Clock` trait for
+//!   `PollingSysTick` and `InterruptSysTick`, and making `SysTickCalibration`s
+//!   from embedded-time rates.
+//! * `critical-section`: guard the crate’s shared state with the
+//!   `critical-section` crate instead of `cortex_m::interrupt::free`. Use this
+//!   on multi-core parts such as the RP2040, or if your program provides its own
+//!   critical section implementation.
+//! * `mock`: [`MockMillis`](struct.MockMillis.html), a counter for unit
+//!   testing code written against `CountsMillis` on the host, and
+//!   [`SimClock`](struct.SimClock.html), for stepping through many timers in
+//!   simulated time.
+//! * `log`: [`uptime`](fn.uptime.html) timestamps for `log::Log`
+//!   implementations, and [`UptimeLogger`](struct.UptimeLogger.html) to add
+//!   them to an existing logger. Turns on `global`.
+//! * `qemu-test`: [`qemu_test`](qemu_test/index.html) helpers for checking
+//!   timings in automated QEMU tests over semihosting.
+//! * `stats`: [`PollingSysTick::stats`](struct.PollingSysTick.html#method.stats),
+//!   which counts wraps and measures the gaps between polls, for finding out
+//!   why delays run slow.
+//! * `nb1`: use `core::convert::Infallible` instead of `void::Void` as the
+//!   [`Never`](type.Never.html) error for `wait_ms`, `poll_tick`, and other
+//!   waits that can’t fail. `nb::Error` is the same type either way, since nb
+//!   0.1.3 re-exports nb 1.0’s.
+//! * `ufmt`: implementations of ufmt’s `uDisplay` trait for `Instant`,
+//!   `SysTickCalibration`, `MillisCountDown`, and the error types.
+//! * `std`: [`StdCountsMillis`](struct.StdCountsMillis.html), a counter backed
+//!   by `std::time::Instant` for running on the host in real time.
 
-use core::{num::Wrapping, time::Duration};
+#[cfg(any(feature = "eh0", feature = "fugit", feature = "embedded-time"))]
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::num::Wrapping;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::time::Duration;
 
 use cortex_m::peripheral::{syst::SystClkSource, SYST};
+#[cfg(not(armv6m))]
+use cortex_m::peripheral::{DCB, DWT};
 
-use embedded_hal::blocking::delay::DelayMs;
-use embedded_hal::timer::CountDown;
+mod alarms;
+#[cfg(feature = "async")]
+mod asynch;
+mod backoff;
+mod blink;
+mod builder;
+#[cfg(feature = "eh0")]
+mod bus;
+#[cfg(feature = "embedded-time")]
+mod clock;
+mod counter;
+mod counter_cell;
+mod critical;
+mod debouncer;
+#[cfg(feature = "defmt")]
+mod defmt_format;
+#[cfg(not(armv6m))]
+mod dwt;
+#[cfg(feature = "eh0")]
+mod eh0;
+#[cfg(feature = "eh1")]
+mod eh1;
+#[cfg(feature = "embassy")]
+mod embassy;
+mod ext;
+mod frame_limiter;
+#[cfg(feature = "fugit-timer")]
+mod fugit_timer;
+#[cfg(feature = "global")]
+pub mod global;
+mod instant;
+mod interrupt;
+mod interval;
+mod iter_until;
+mod latency;
+mod manual;
+mod metronome;
+#[cfg(feature = "mock")]
+mod mock;
+mod monostable;
+mod observer;
+mod output;
+mod panic_delay;
+mod periodic;
+mod pool;
+mod profiler;
+#[cfg(feature = "qemu-test")]
+pub mod qemu_test;
+mod ramp;
+mod rate_meter;
+mod reload;
+#[cfg(feature = "rt")]
+mod rt;
+#[cfg(feature = "rtic")]
+pub mod rtic;
+#[cfg(feature = "rtic1")]
+pub mod rtic1;
+mod scheduler;
+#[cfg(feature = "eh0")]
+mod serial;
+mod shared;
+#[cfg(feature = "mock")]
+mod sim;
+mod singleton;
+#[cfg(not(armv6m))]
+mod slip;
+mod soft_pwm;
+mod soft_watchdog;
+mod state;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "std")]
+mod std_clock;
+mod stopwatch;
+mod throttle;
+mod timed_fsm;
+mod timeout;
+#[cfg(feature = "eh0")]
+mod timer;
+mod timer_queue;
+#[cfg(feature = "eh0")]
+mod typed;
+#[cfg(feature = "ufmt")]
+mod ufmt_display;
+#[cfg(feature = "log")]
+mod uptime;
+#[cfg(any(feature = "async", feature = "embassy"))]
+mod wakers;
+mod wall_clock;
 
-use nb;
-use void::Void;
+use counter::Counter64;
+use reload::{ticks_into_period, ReloadDither, TickReload};
+#[cfg(not(armv6m))]
+use slip::WrapTracker;
+#[cfg(feature = "stats")]
+use stats::PollTracker;
 
-use core::cell::UnsafeCell;
+pub use alarms::{AlarmId, Alarms, AlarmsError};
+#[cfg(feature = "async")]
+pub use asynch::{wake_expired_timers, Ticker, TimerFuture};
+pub use backoff::Backoff;
+pub use blink::{blink_patterns, BlinkPattern};
+pub use builder::{InterruptSysTickBuilder, PollingSysTickBuilder};
+#[cfg(feature = "eh0")]
+pub use bus::{recover_i2c_bus, TimedSpi};
+pub use counter_cell::{CounterCell, StaticCountDown};
+pub use debouncer::Debouncer;
+#[cfg(not(armv6m))]
+pub use dwt::DwtCountsMillis;
+pub use ext::{SysTickExt, SysTickPeripheral};
+pub use frame_limiter::FrameLimiter;
+pub use instant::Instant;
+pub use interrupt::InterruptSysTick;
+pub use interval::Interval;
+pub use iter_until::IterUntil;
+pub use latency::SysTickSnapshot;
+pub use manual::ManualTickCounter;
+pub use metronome::{CatchUp, Metronome};
+#[cfg(feature = "mock")]
+pub use mock::MockMillis;
+pub use monostable::Monostable;
+pub use observer::ClockObserver;
+pub use output::Output;
+#[cfg(feature = "eh0")]
+pub use output::PinOutput;
+pub use panic_delay::panic_delay_ms;
+pub use periodic::PeriodicMillisCountDown;
+pub use pool::{CountDownPool, CountDownPoolError, PoolTimer};
+pub use profiler::{ProfileStats, Profiler};
+pub use ramp::Ramp;
+pub use rate_meter::RateMeter;
+#[cfg(feature = "rt")]
+pub use rt::set_tick_hook;
+pub use scheduler::{Scheduler, SchedulerError, TaskId};
+#[cfg(feature = "eh0")]
+pub use serial::TimedSerial;
+pub use shared::{SharedSysTick, SysTickHandle};
+#[cfg(feature = "mock")]
+pub use sim::{Fired, SimClock, SimRun};
+#[cfg(not(armv6m))]
+pub use slip::{SlippageMode, TimeSlippage};
+pub use soft_pwm::{PwmChannel, SoftPwm, SoftPwmError};
+pub use soft_watchdog::SoftWatchdog;
+pub use state::{Running, Stopped};
+#[cfg(feature = "stats")]
+pub use stats::PollStats;
+#[cfg(feature = "std")]
+pub use std_clock::StdCountsMillis;
+pub use stopwatch::Stopwatch;
+pub use throttle::Throttle;
+pub use timed_fsm::{StateTimeout, TimedFsm};
+pub use timeout::{
+    retry_ms, wait_with_deadline, with_timeout_ms, RetryError, TimeoutError, TimeoutExt,
+};
+#[cfg(feature = "eh0")]
+pub use timer::TimerCountsMillis;
+pub use timer_queue::TimerQueue;
+#[cfg(all(feature = "eh0", feature = "fugit"))]
+pub use typed::MillisCountDownFugit;
+#[cfg(feature = "eh0")]
+pub use typed::{CountDownTime, MillisCountDownU32, TypedMillisCountDown};
+#[cfg(feature = "log")]
+pub use uptime::{uptime, Uptime, UptimeLogger};
+pub use wall_clock::{WallClock, WallClockError};
 
 /// Trait that abstracts a counter that increases as milliseconds go by.
 ///
-/// Factored out to leave the door open for different SysTick counters, such as
-/// counting via interrupts.
+/// Implemented by both [`PollingSysTick`](struct.PollingSysTick.html) and
+/// [`InterruptSysTick`](struct.InterruptSysTick.html).
 pub trait CountsMillis {
     /// Returns a value that must not increment faster than once per
     /// millisecond, and will wrap around.
     fn count(&self) -> Wrapping<u32>;
+
+    /// Returns how many milliseconds the count can go up by at once.
+    ///
+    /// This is 1 for most counters, but a `PollingSysTick` built with a
+    /// longer [`tick_ms`](struct.PollingSysTickBuilder.html#method.tick_ms)
+    /// counts in steps of that size.
+    fn resolution_ms(&self) -> u32 {
+        1
+    }
+
+    /// Blocks until the count is past `deadline`.
+    ///
+    /// Unlike a delay, this doesn’t drift when used to pace a loop, since each
+    /// deadline can be worked out from the last one:
+    ///
+    /// ```ignore
+    /// let mut next = Instant::now(&systick);
+    ///
+    /// loop {
+    ///     next += 10;
+    ///     systick.delay_until(next);
+    ///     run_control_loop();
+    /// }
+    /// ```
+    ///
+    /// Returns right away if `deadline` has already passed.
+    fn delay_until(&self, deadline: Instant)
+    where
+        Self: Sized,
+    {
+        let mut count_down = MillisCountDown::new(self);
+        count_down.start_until(deadline);
+        nb::block!(count_down.wait_ms()).unwrap();
+    }
+
+    /// Returns an iterator that yields until `ms` milliseconds have passed,
+    /// for polling loops with a time limit:
+    ///
+    /// ```ignore
+    /// for _ in systick.iter_until_ms(100) {
+    ///     if sensor.is_ready() {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Each item is the number of milliseconds since the iterator was created.
+    fn iter_until_ms(&self, ms: u32) -> IterUntil<'_, Self>
+    where
+        Self: Sized,
+    {
+        IterUntil::new(self, ms)
+    }
+
+    /// Blocks for at least `ms` milliseconds, calling `f` over and over while
+    /// it waits.
+    ///
+    /// Use this for work that can’t be put off for the whole delay, like
+    /// feeding a watchdog or polling a USB stack:
+    ///
+    /// ```ignore
+    /// systick.delay_ms_with(500, || {
+    ///     watchdog.feed();
+    ///     usb_dev.poll(&mut [&mut serial]);
+    /// });
+    /// ```
+    ///
+    /// `f` is called at least once, even for a delay of 0.
+    fn delay_ms_with<F: FnMut()>(&self, ms: u32, mut f: F)
+    where
+        Self: Sized,
+    {
+        let mut count_down = MillisCountDown::new(self);
+        count_down.start_ms_at_least(ms);
+
+        loop {
+            f();
+
+            if count_down.wait_ms().is_ok() {
+                break;
+            }
+        }
+    }
+
+    /// Returns the current count as a fugit `Instant` with millisecond ticks.
+    ///
+    /// fugit’s `Instant` comparisons are wrap-aware, just like
+    /// `MillisCountDown`’s.
+    #[cfg(feature = "fugit")]
+    fn fugit_instant(&self) -> fugit::TimerInstantU32<1_000> {
+        fugit::TimerInstantU32::from_ticks(self.count().0)
+    }
+}
+
+/// Extension of [`CountsMillis`](trait.CountsMillis.html) for counters that
+/// also keep a 64-bit count.
+///
+/// A `u32` of milliseconds wraps after about 49.7 days, which is fine for
+/// timing intervals shorter than about 24.8 days. A `u64` of milliseconds
+/// won’t wrap for more than 500 million years, so you can use it (or
+/// [`MillisCountDown64`](struct.MillisCountDown64.html)) to time arbitrarily
+/// long intervals.
+pub trait CountsMillis64: CountsMillis {
+    /// Returns the same count as [`count`](trait.CountsMillis.html#tymethod.count),
+    /// but without wrapping at `u32::MAX`.
+    fn count64(&self) -> u64;
+
+    /// Returns the time since the counter was created (for the SysTick
+    /// counters, since `new` was called), for logging or timestamps.
+    fn uptime(&self) -> Duration {
+        Duration::from_millis(self.count64())
+    }
+}
+
+/// Trait that abstracts a counter that increases as microseconds go by.
+///
+/// A `u32` of microseconds wraps after about 71.6 minutes, so the same
+/// rollover-safe comparisons as for milliseconds are needed.
+pub trait CountsMicros {
+    /// Returns a value that increases once per microsecond (or as close as the
+    /// counter can manage), and will wrap around.
+    fn count_micros(&self) -> Wrapping<u32>;
 }
 
 /// Configuration information for setting the SysTick reload value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SysTickCalibration {
     /// The number of ticks of the SysTick’s clock source to get to 1ms.
     ///
@@ -50,6 +467,14 @@ pub struct SysTickCalibration {
     /// clock cycles, since an extra one is always needed to detect the rollover
     /// and reload the counter.
     pub ticks_per_ms: u32,
+
+    /// The fraction of a tick (out of 2³²) that a millisecond takes beyond
+    /// `ticks_per_ms + 1` ticks, for clocks that aren’t a multiple of 1kHz.
+    ///
+    /// SysTick can only count whole ticks, so the counters make up for this by
+    /// occasionally making a millisecond one tick longer. Without it, a clock
+    /// like 32.768kHz would count about 2.4% fast.
+    pub fractional_ticks: u32,
 }
 
 impl SysTickCalibration {
@@ -59,30 +484,332 @@ impl SysTickCalibration {
     /// (SAMD51 for example seems to not have it, since its processor speed is
     /// configurable). In those cases, use
     /// [`from_clock_hz`](#method.from_clock_hz) instead.
+    ///
+    /// Some chips mark this value as inexact, in which case it’s returned
+    /// anyway. Use [`built_in_with_source`](#method.built_in_with_source) to
+    /// find out, or [`built_in_exact`](#method.built_in_exact) to only accept
+    /// an exact one.
     pub fn built_in() -> Option<SysTickCalibration> {
         let calibrated_tick_value = SYST::get_ticks_per_10ms();
 
         if calibrated_tick_value == 0 {
             None
         } else {
+            let ticks_per_10ms = calibrated_tick_value + 1;
+
             Some(SysTickCalibration {
                 // Leave one clock cycle for checking the overflow
-                ticks_per_ms: (calibrated_tick_value + 1) / 10 - 1,
+                ticks_per_ms: ticks_per_10ms / 10 - 1,
+                fractional_ticks: fraction(ticks_per_10ms % 10, 10),
             })
         }
     }
 
+    /// Like [`built_in`](#method.built_in), but also says whether the chip
+    /// marks the value as exact, from the SKEW bit of SysTick’s calibration
+    /// register.
+    pub fn built_in_with_source() -> Option<(SysTickCalibration, CalibrationSource)> {
+        let source = if SYST::is_precise() {
+            CalibrationSource::Exact
+        } else {
+            CalibrationSource::Skewed
+        };
+
+        SysTickCalibration::built_in().map(|calibration| (calibration, source))
+    }
+
+    /// Like [`built_in`](#method.built_in), but returns `None` if the chip
+    /// marks the value as inexact.
+    pub fn built_in_exact() -> Option<SysTickCalibration> {
+        match SysTickCalibration::built_in_with_source() {
+            Some((calibration, CalibrationSource::Exact)) => Some(calibration),
+            _ => None,
+        }
+    }
+
     /// Creates a calibration from the underlying frequency of the clock that
     /// drives SysTick. This typically seems to be the same frequency that the
     /// processor is currently running at.
     ///
     /// For SAMD51 processors, if you don’t want to hard-code a known size, you
     /// can get this from the gclck0 frequency.
-    pub fn from_clock_hz(hz: u32) -> SysTickCalibration {
-        SysTickCalibration {
+    ///
+    /// Returns an error if `hz` is below [`MIN_CLOCK_HZ`](#associatedconstant.MIN_CLOCK_HZ),
+    /// since SysTick can’t count a millisecond with a reload value of 0. (Any
+    /// `u32` frequency is low enough for a 1ms reload to fit in SysTick’s 24
+    /// bits, but see [`PollingSysTick::with_tick_ms`](struct.PollingSysTick.html#method.with_tick_ms)
+    /// for longer ticks.)
+    pub fn from_clock_hz(hz: u32) -> Result<SysTickCalibration, CalibrationError> {
+        if hz < Self::MIN_CLOCK_HZ {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        Ok(SysTickCalibration {
             ticks_per_ms: hz / 1_000 - 1,
+            fractional_ticks: fraction(hz % 1_000, 1_000),
+        })
+    }
+
+    /// Version of [`from_clock_hz`](#method.from_clock_hz) that rounds to a
+    /// whole number of ticks per millisecond.
+    ///
+    /// `from_clock_hz` keeps the fraction of a tick, which the counters make up
+    /// for by sometimes making a millisecond one tick longer. That’s exact on
+    /// average, but the milliseconds aren’t all the same length. A rounded
+    /// calibration makes every millisecond the same, at the cost of counting a
+    /// little fast or slow; see [`error_ppm`](#method.error_ppm) for how much.
+    pub fn from_clock_hz_rounded(
+        hz: u32,
+        rounding: Rounding,
+    ) -> Result<SysTickCalibration, CalibrationError> {
+        if hz < Self::MIN_CLOCK_HZ {
+            return Err(CalibrationError::ClockTooSlow);
         }
+
+        let ticks = match rounding {
+            Rounding::Down => hz / 1_000,
+            Rounding::Up => hz.div_ceil(1_000),
+            Rounding::Nearest => hz / 1_000 + u32::from(hz % 1_000 >= 500),
+        };
+
+        Ok(SysTickCalibration {
+            ticks_per_ms: ticks - 1,
+            fractional_ticks: 0,
+        })
+    }
+
+    /// Returns the clock frequency that this calibration is for, rounded to
+    /// the nearest Hz.
+    pub fn clock_hz(&self) -> u32 {
+        let hz = (u128::from(self.ticks_per_ms_fixed()) * 1_000 + (1 << 31)) >> 32;
+        hz.min(u128::from(u32::MAX)) as u32
+    }
+
+    /// Returns how fast (positive) or slow (negative) the counters will run, in
+    /// parts per million, with this calibration on a clock that is actually
+    /// `actual_hz`.
+    ///
+    /// For a calibration made by `from_clock_hz(actual_hz)` this is 0, or
+    /// close to it.
+    pub fn error_ppm(&self, actual_hz: u32) -> i32 {
+        let assumed = i128::from(self.ticks_per_ms_fixed());
+        let actual = (i128::from(actual_hz) << 32) / 1_000;
+
+        let ppm = (actual - assumed) * 1_000_000 / assumed;
+        ppm.clamp(i128::from(i32::MIN), i128::from(i32::MAX)) as i32
+    }
+
+    /// Converts a number of SysTick ticks to nanoseconds at this calibration’s
+    /// clock rate, rounded to the nearest nanosecond.
+    pub fn ticks_to_nanos(&self, ticks: u32) -> u64 {
+        let fixed = u128::from(self.ticks_per_ms_fixed());
+        let nanos = (((u128::from(ticks) * 1_000_000) << 32) + fixed / 2) / fixed;
+        nanos.min(u128::from(u64::MAX)) as u64
     }
+
+    /// Ticks per millisecond as 32.32 fixed point
+    fn ticks_per_ms_fixed(&self) -> u64 {
+        (u64::from(self.ticks_per_ms) + 1) << 32 | u64::from(self.fractional_ticks)
+    }
+
+    /// Version of [`from_clock_hz`](#method.from_clock_hz) that takes a fugit
+    /// rate, such as `48.MHz()`.
+    #[cfg(feature = "fugit")]
+    pub fn from_fugit(rate: fugit::HertzU32) -> Result<SysTickCalibration, CalibrationError> {
+        SysTickCalibration::from_clock_hz(rate.raw())
+    }
+
+    /// Version of [`from_clock_hz`](#method.from_clock_hz) that takes an
+    /// embedded-time rate, such as `Hertz(48_000_000)` or `MegaHertz(48)`.
+    ///
+    /// Returns `CalibrationError::ReloadTooLarge` for rates too fast to fit in
+    /// a `u32` of Hz.
+    #[cfg(feature = "embedded-time")]
+    pub fn from_embedded_time<R: Into<embedded_time::rate::Hertz<u64>>>(
+        rate: R,
+    ) -> Result<SysTickCalibration, CalibrationError> {
+        let hz = u32::try_from(rate.into().0).map_err(|_| CalibrationError::ReloadTooLarge)?;
+        SysTickCalibration::from_clock_hz(hz)
+    }
+
+    /// Measures SysTick’s clock against a trusted reference timer, such as
+    /// an RTC or a crystal-driven timer, for when the core clock isn’t known
+    /// accurately.
+    ///
+    /// Counts SysTick’s ticks while `reference` times `window`, which must be
+    /// `window_ms` milliseconds long. (The two have to be given separately
+    /// since every HAL’s `CountDown` has its own `Time` type.) Longer windows
+    /// give more accurate calibrations.
+    ///
+    /// ```ignore
+    /// let calibration =
+    ///     SysTickCalibration::measure_against(&mut core.SYST, &mut rtc_timer, 1.secs(), 1_000)?;
+    /// let systick = PollingSysTick::new(core.SYST, &calibration);
+    /// ```
+    ///
+    /// SysTick is left stopped, ready to be used by a counter. Returns
+    /// `CalibrationError::ClockTooSlow` if `window_ms` is 0 or SysTick’s clock
+    /// is below [`MIN_CLOCK_HZ`](#associatedconstant.MIN_CLOCK_HZ).
+    #[cfg(feature = "eh0")]
+    pub fn measure_against<CD, T>(
+        syst: &mut SYST,
+        reference: &mut CD,
+        window: T,
+        window_ms: u32,
+    ) -> Result<SysTickCalibration, CalibrationError>
+    where
+        CD: embedded_hal::timer::CountDown,
+        T: Into<CD::Time>,
+    {
+        if window_ms == 0 {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        syst.disable_counter();
+        syst.disable_interrupt();
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(Self::MAX_RELOAD);
+        syst.clear_current();
+        // Clear COUNTFLAG
+        syst.has_wrapped();
+        syst.enable_counter();
+
+        reference.start(window);
+        let start = SYST::get_current();
+        let mut wraps: u64 = 0;
+
+        while reference.wait().is_err() {
+            if syst.has_wrapped() {
+                wraps += 1;
+            }
+        }
+
+        let end = SYST::get_current();
+
+        // A wrap between our last check and reading `end` left it near the
+        // top of the range. One just after reading it belongs to after the
+        // window.
+        if syst.has_wrapped() && end > Self::MAX_RELOAD / 2 {
+            wraps += 1;
+        }
+
+        syst.disable_counter();
+
+        // SysTick counts down, and wraps every MAX_RELOAD + 1 ticks.
+        let ticks = (wraps * (u64::from(Self::MAX_RELOAD) + 1) + u64::from(start))
+            .saturating_sub(u64::from(end));
+
+        // Ticks per millisecond as 32.32 fixed point
+        let ticks_per_ms = (u128::from(ticks) << 32) / u128::from(window_ms);
+        let whole_ticks = (ticks_per_ms >> 32) as u64;
+
+        if whole_ticks < u64::from(Self::MIN_CLOCK_HZ / 1_000) {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        Ok(SysTickCalibration {
+            ticks_per_ms: u32::try_from(whole_ticks - 1)
+                .map_err(|_| CalibrationError::ReloadTooLarge)?,
+            fractional_ticks: ticks_per_ms as u32,
+        })
+    }
+
+    /// The slowest clock that SysTick can count milliseconds with.
+    pub const MIN_CLOCK_HZ: u32 = 2_000;
+
+    /// The largest possible reload value, since SysTick’s reload register is 24
+    /// bits. A tick of `n` milliseconds can be used with clocks up to
+    /// `(MAX_RELOAD + 1) * 1000 / n` Hz.
+    pub const MAX_RELOAD: u32 = 0x00FF_FFFF;
+
+    /// Returns the reload value for SysTick to wrap every `tick_ms`
+    /// milliseconds, if it fits in SysTick’s 24 bits.
+    pub(crate) fn reload_for_tick_ms(&self, tick_ms: u32) -> Result<TickReload, CalibrationError> {
+        if self.ticks_per_ms == 0 || tick_ms == 0 {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        match self.ticks_per_ms_fixed().checked_mul(u64::from(tick_ms)) {
+            Some(ticks) => TickReload::from_fixed(ticks),
+            None => Err(CalibrationError::ReloadTooLarge),
+        }
+    }
+
+    /// The reload for a 1ms tick. Unlike `reload_for_tick_ms` this doesn’t
+    /// check anything: `new` uses `ticks_per_ms` as it is.
+    pub(crate) fn reload_for_ms(&self) -> TickReload {
+        TickReload {
+            reload: self.ticks_per_ms,
+            fraction: self.fractional_ticks,
+        }
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl TryFrom<fugit::HertzU32> for SysTickCalibration {
+    type Error = CalibrationError;
+
+    /// Same as [`from_fugit`](#method.from_fugit).
+    fn try_from(rate: fugit::HertzU32) -> Result<Self, CalibrationError> {
+        SysTickCalibration::from_fugit(rate)
+    }
+}
+
+#[cfg(feature = "embedded-time")]
+impl TryFrom<embedded_time::rate::Hertz> for SysTickCalibration {
+    type Error = CalibrationError;
+
+    /// Same as [`from_embedded_time`](#method.from_embedded_time).
+    fn try_from(rate: embedded_time::rate::Hertz) -> Result<Self, CalibrationError> {
+        SysTickCalibration::from_embedded_time(rate)
+    }
+}
+
+/// `numerator / denominator` as a fraction out of 2³². `numerator` must be
+/// less than `denominator`.
+fn fraction(numerator: u32, denominator: u32) -> u32 {
+    ((u64::from(numerator) << 32) / u64::from(denominator)) as u32
+}
+
+/// How [`SysTickCalibration::from_clock_hz_rounded`](struct.SysTickCalibration.html#method.from_clock_hz_rounded)
+/// rounds to a whole number of ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rounding {
+    /// Round to the nearest tick, for the smallest error.
+    Nearest,
+    /// Round up, so that milliseconds are never short and the count runs
+    /// slow, if anything.
+    Up,
+    /// Round down, so that milliseconds are never long and the count runs
+    /// fast, if anything.
+    Down,
+}
+
+/// How accurate a chip says its built-in calibration is. Returned by
+/// [`SysTickCalibration::built_in_with_source`](struct.SysTickCalibration.html#method.built_in_with_source).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CalibrationSource {
+    /// The value is exactly 10ms of ticks.
+    Exact,
+    /// The value is only approximate, because the clock that drives SysTick
+    /// can’t count exactly 10ms. Delays may be noticeably off.
+    Skewed,
+}
+
+/// Errors from creating a [`SysTickCalibration`](struct.SysTickCalibration.html)
+/// or configuring SysTick with one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CalibrationError {
+    /// The clock is too slow for SysTick to count milliseconds with.
+    ClockTooSlow,
+    /// The reload value doesn’t fit in SysTick’s 24-bit reload register.
+    ReloadTooLarge,
+    /// The external reference clock was asked for, but this chip doesn’t have
+    /// one.
+    NoReferenceClock,
 }
 
 /// Millisecond counter based on SysTick
@@ -117,30 +844,462 @@ impl SysTickCalibration {
 /// method is being actively called, and may experience some jitter depending on
 /// where SysTick is in its count when you start a timer.
 ///
-/// This also means we need to use internal mutability so that we can access the
-/// SYST.has_wrapped() method (which mutates on read) and update our counter.
-pub struct PollingSysTick {
-    syst: UnsafeCell<SYST>,
-    counter: UnsafeCell<Wrapping<u32>>,
+/// Checking for a wrap clears SysTick’s COUNTFLAG, so polling has to update our
+/// count as a side effect of reading it. We do that through a shared
+/// reference: the hardware clears the flag atomically as it’s read, so only one
+/// caller can ever see a given wrap, and the count itself is incremented
+/// atomically (with a critical section on thumbv6, or when the low 32 bits roll
+/// over). This makes `PollingSysTick` `Sync`, so it can be stored in a `static`
+/// and polled from interrupt handlers as well as your main loop.
+///
+/// ## Missed wraps
+///
+/// If `count` isn’t called for more than a millisecond, SysTick can wrap more
+/// than once between polls, but we can only see that it wrapped at least once.
+/// The lost milliseconds make the count run slow. On cores with a DWT cycle
+/// counter, [`track_slippage`](#method.track_slippage) uses it to notice these
+/// missed wraps, and either report them or add them back to the count.
+pub struct PollingSysTick<S = Running> {
+    syst: SYST,
+    /// Milliseconds per SysTick wrap.
+    tick_ms: u32,
+    /// The calibration that `base_tick` was made from.
+    calibration: SysTickCalibration,
+    /// Slippage tracking and clock changes only apply to the core clock.
+    clock_source: SystClkSource,
+    counter: Counter64,
+    /// Tick length from the calibration, before trimming.
+    base_tick: TickReload,
+    trim_ppm: i32,
+    dither: ReloadDither,
+    /// Fraction of a tick (out of 2³²) left over from changing calibrations,
+    /// which hasn’t added up to a whole tick yet.
+    partial_tick: AtomicU32,
+    /// Set between start_tickless and end_tickless.
+    tickless: AtomicBool,
+    #[cfg(not(armv6m))]
+    slip: WrapTracker,
+    #[cfg(feature = "stats")]
+    stats: PollTracker,
+    /// When the `CountDown` implemented on `PollingSysTick` itself finishes.
+    #[cfg(feature = "eh0")]
+    pub(crate) deadline: Option<Wrapping<u32>>,
+    state: PhantomData<S>,
 }
 
+// SYST isn’t Sync, but nothing that takes &PollingSysTick uses it: counting
+// reads COUNTFLAG directly from the register block (see syst_has_wrapped), and
+// the Counter64 is only changed through Counter64::add, which is safe to call
+// from more than one context at once. dither and partial_tick are only changed
+// inside critical sections.
+unsafe impl<S> Sync for PollingSysTick<S> {}
+
 impl PollingSysTick {
     /// Configures SysTick based on the values provided in the calibration.
-    pub fn new(mut syst: SYST, calibration: &SysTickCalibration) -> Self {
+    ///
+    /// Use [`builder`](#method.builder) for other configurations.
+    pub fn new<P: SysTickPeripheral>(syst: P, calibration: &SysTickCalibration) -> Self {
+        let tick = calibration.reload_for_ms();
+
+        Self::configure(
+            syst.into_syst(),
+            calibration,
+            tick,
+            tick,
+            &PollingSysTickBuilder::new(),
+        )
+    }
+
+    /// Starts configuring a `PollingSysTick` with more options than `new`.
+    pub fn builder() -> PollingSysTickBuilder {
+        PollingSysTickBuilder::new()
+    }
+
+    /// Like [`new`](#method.new), but clocks SysTick from the external
+    /// reference clock (STCLK) rather than the core clock. Same as
+    /// [`external_clock`](struct.PollingSysTickBuilder.html#method.external_clock).
+    ///
+    /// Returns `syst` back as an error if this chip has no reference clock.
+    pub fn with_external_clock<P: SysTickPeripheral>(
+        syst: P,
+        calibration: &SysTickCalibration,
+    ) -> Result<Self, SYST> {
+        Self::builder()
+            .external_clock()
+            .build(syst, calibration)
+            .map_err(|(syst, _)| syst)
+    }
+
+    /// Like [`new`](#method.new), but SysTick only wraps every `tick_ms`
+    /// milliseconds. Same as
+    /// [`tick_ms`](struct.PollingSysTickBuilder.html#method.tick_ms).
+    ///
+    /// SysTick’s reload register is 24 bits, so the whole tick has to fit in
+    /// 2²⁴ clock cycles. If it doesn’t (or if `tick_ms` is 0), returns `syst`
+    /// back along with the reason.
+    pub fn with_tick_ms<P: SysTickPeripheral>(
+        syst: P,
+        calibration: &SysTickCalibration,
+        tick_ms: u32,
+    ) -> Result<Self, (SYST, CalibrationError)> {
+        Self::builder().tick_ms(tick_ms).build(syst, calibration)
+    }
+
+    /// Sets up SysTick. `base_tick` is the tick from the calibration, and
+    /// `tick` is the same after trimming.
+    pub(crate) fn configure<S>(
+        mut syst: SYST,
+        calibration: &SysTickCalibration,
+        base_tick: TickReload,
+        tick: TickReload,
+        settings: &PollingSysTickBuilder<S>,
+    ) -> Self {
+        let dither = ReloadDither::new();
+        let counter = Counter64::new();
+
+        syst.disable_counter();
         syst.disable_interrupt();
-        syst.set_clock_source(SystClkSource::Core);
-        syst.set_reload(calibration.ticks_per_ms);
-        syst.enable_counter();
+        syst.set_clock_source(settings.clock_source);
+        dither.set(tick);
+        dither.restart();
+        counter.set(settings.initial_count);
+
+        if settings.interrupt {
+            syst.enable_interrupt();
+        }
+
+        if settings.enabled {
+            syst.enable_counter();
+        }
 
         PollingSysTick {
-            syst: UnsafeCell::new(syst),
-            counter: UnsafeCell::default(),
+            syst,
+            tick_ms: settings.tick_ms,
+            calibration: *calibration,
+            clock_source: settings.clock_source,
+            counter,
+            base_tick,
+            trim_ppm: settings.trim_ppm,
+            dither,
+            partial_tick: AtomicU32::new(0),
+            tickless: AtomicBool::new(false),
+            #[cfg(not(armv6m))]
+            slip: WrapTracker::new(),
+            #[cfg(feature = "stats")]
+            stats: PollTracker::new(),
+            #[cfg(feature = "eh0")]
+            deadline: None,
+            state: PhantomData,
+        }
+    }
+
+    /// Blocks for `ms` milliseconds, sleeping with `wfi` between ticks instead
+    /// of polling at full power.
+    ///
+    /// The SysTick exception is enabled during the delay (and disabled again
+    /// afterwards, unless it was already on) so that it can wake the
+    /// processor. Your program must define a SysTick handler, as described
+    /// for [`interrupt`](struct.PollingSysTickBuilder.html#method.interrupt),
+    /// and interrupts must be enabled. Other interrupts also wake the
+    /// processor, so they’ll still be handled promptly.
+    pub fn delay_ms_low_power(&mut self, ms: u32) {
+        let interrupt_was_enabled = self.set_interrupt(true);
+
+        sleep_ms(&*self, ms);
+
+        if !interrupt_was_enabled {
+            self.set_interrupt(false);
+        }
+    }
+
+    /// Turns the SysTick exception on or off, returning whether it was on.
+    ///
+    /// `SYST`’s own methods read the control register to change it, which
+    /// clears COUNTFLAG, so this reads it just once and counts any wrap that
+    /// was pending.
+    fn set_interrupt(&mut self, enabled: bool) -> bool {
+        critical::free(|_| {
+            let csr = self.syst.csr.read();
+
+            if csr & SYST_CSR_COUNTFLAG != 0 && !self.tickless.load(Ordering::Relaxed) {
+                self.on_wrap();
+            }
+
+            let tickint = if enabled {
+                csr | SYST_CSR_TICKINT
+            } else {
+                csr & !SYST_CSR_TICKINT
+            };
+
+            // COUNTFLAG is read-only, so writing it back has no effect.
+            unsafe { self.syst.csr.write(tickint) };
+
+            csr & SYST_CSR_TICKINT != 0
+        })
+    }
+
+    /// Advances the count for a wrap of SysTick.
+    fn on_wrap(&self) {
+        self.counter.add(self.tick_ms);
+        self.dither.on_wrap();
+
+        #[cfg(not(armv6m))]
+        self.slip
+            .on_wrap(&self.counter, self.tick_ms, self.dither.running_reload());
+    }
+
+    /// Turns this value back into the underlying SysTick, along with the
+    /// final millisecond count.
+    ///
+    /// SysTick is left as it was out of reset: stopped, with its exception
+    /// off, its reload and current values cleared, and clocked from the core
+    /// clock. Pass the count to
+    /// [`initial_count`](struct.PollingSysTickBuilder.html#method.initial_count)
+    /// to carry on counting from it.
+    pub fn free(self) -> (SYST, u64) {
+        self.stop().free()
+    }
+
+    /// Changes SysTick’s reload value to match a new calibration, without
+    /// losing any time that has been counted.
+    ///
+    /// Use this when changing the speed of the clock that SysTick runs from,
+    /// such as dropping to a low-power clock. Call it right after the clock
+    /// changes. The tick length (see [`with_tick_ms`](#method.with_tick_ms))
+    /// stays the same.
+    ///
+    /// SysTick has to restart its current tick to pick up the new reload
+    /// value. The part of the tick that had already gone by is carried over,
+    /// and added to the count once these partial ticks add up to a whole one.
+    pub fn set_calibration(
+        &mut self,
+        calibration: &SysTickCalibration,
+    ) -> Result<(), CalibrationError> {
+        let base_tick = calibration.reload_for_tick_ms(self.tick_ms)?;
+        let tick = base_tick.trimmed(self.trim_ppm)?;
+
+        self.base_tick = base_tick;
+        self.calibration = *calibration;
+
+        critical::free(|_| {
+            // Count any wrap that has already happened, so that the current
+            // value only tells us about the partial tick.
+            self.count();
+
+            let ticks_in = ticks_into_period(self.dither.running_reload());
+            self.credit_clock_ticks(u64::from(ticks_in));
+
+            self.dither.set(tick);
+            self.syst.clear_current();
+            self.dither.restart();
+
+            #[cfg(not(armv6m))]
+            self.slip.resync(self.dither.running_reload());
+
+            #[cfg(feature = "stats")]
+            self.stats.resync();
+        });
+
+        Ok(())
+    }
+
+    /// Corrects for a clock that runs `ppm` parts per million fast (or slow,
+    /// if negative) by lengthening (or shortening) each tick to match.
+    ///
+    /// Use this if you’ve measured your oscillator against a better reference,
+    /// such as a GPS PPS signal or network time. At 1ppm a day is off by about
+    /// 86ms, so a few ppm of trim can take a logger from drifting seconds per
+    /// day to well under one. Replaces any previous trim, and takes effect from
+    /// the next tick.
+    ///
+    /// Returns an error, and leaves the trim unchanged, if the trimmed tick
+    /// wouldn’t fit in SysTick’s reload register.
+    pub fn set_trim_ppm(&mut self, ppm: i32) -> Result<(), CalibrationError> {
+        self.dither.set(self.base_tick.trimmed(ppm)?);
+        self.trim_ppm = ppm;
+
+        Ok(())
+    }
+
+    /// Returns the trim set by [`set_trim_ppm`](#method.set_trim_ppm).
+    pub fn trim_ppm(&self) -> i32 {
+        self.trim_ppm
+    }
+
+    /// Returns the calibration in use, from when this was created or the last
+    /// [`set_calibration`](#method.set_calibration).
+    pub fn calibration(&self) -> SysTickCalibration {
+        self.calibration
+    }
+
+    /// Returns how many milliseconds each SysTick wrap counts as.
+    pub fn tick_ms(&self) -> u32 {
+        self.tick_ms
+    }
+
+    /// Returns how many times a second SysTick wraps, rounded to the nearest
+    /// Hz. This is 1000 unless it was built with a longer
+    /// [`tick_ms`](struct.PollingSysTickBuilder.html#method.tick_ms).
+    pub fn tick_hz(&self) -> u32 {
+        (1_000 + self.tick_ms / 2) / self.tick_ms
+    }
+
+    /// Returns SysTick’s reload value, including any
+    /// [trim](#method.set_trim_ppm).
+    ///
+    /// If the calibration has a fraction of a tick, SysTick’s reload register
+    /// is sometimes one more than this, to make up for it.
+    pub fn reload(&self) -> u32 {
+        self.dither.tick().reload
+    }
+
+    /// Adds `ticks` of SysTick’s clock to the count, using the current tick
+    /// length. Whole ticks are counted right away, and what’s left over is
+    /// saved in `partial_tick` until it adds up to a whole one. Returns the
+    /// milliseconds that were counted.
+    ///
+    /// Must be called inside a critical section.
+    fn credit_clock_ticks(&self, ticks: u64) -> u32 {
+        let tick = u128::from(self.dither.tick().to_fixed());
+        let ticks = ((u128::from(ticks) << 64) / tick) as u64
+            + u64::from(self.partial_tick.load(Ordering::Relaxed));
+
+        let ms = ((ticks >> 32) as u32).saturating_mul(self.tick_ms);
+
+        self.partial_tick.store(ticks as u32, Ordering::Relaxed);
+
+        if ms > 0 {
+            self.counter.add(ms);
         }
+
+        ms
     }
 
-    /// Turns this value back into the underlying SysTick.
-    pub fn free(self) -> SYST {
-        self.syst.into_inner()
+    /// Lets SysTick run for up to `idle_ms` milliseconds without wrapping, so
+    /// that a processor that’s going to be idle isn’t woken every tick.
+    ///
+    /// SysTick is reprogrammed to wrap once at the end of the idle time, which
+    /// is shortened (and returned) if it doesn’t fit in SysTick’s 24 bits.
+    /// Enable the SysTick exception (see
+    /// [`interrupt`](struct.PollingSysTickBuilder.html#method.interrupt)) if
+    /// you want it to wake the processor then.
+    ///
+    /// Call [`end_tickless`](#method.end_tickless) when the processor wakes
+    /// up, for whatever reason, to go back to normal ticks and count the time
+    /// that went by. Until then, the count stays where it was.
+    pub fn start_tickless(&mut self, idle_ms: u32) -> u32 {
+        critical::free(|_| {
+            // Count anything up to now, including the partial tick, since
+            // we’re about to restart SysTick’s period.
+            self.count();
+            self.credit_clock_ticks(u64::from(ticks_into_period(self.dither.running_reload())));
+
+            let tick = self.dither.tick().to_fixed();
+            let max_ticks = ((u64::from(SysTickCalibration::MAX_RELOAD) + 1) << 32) / tick;
+            let idle_ticks = u64::from((idle_ms / self.tick_ms).max(1)).min(max_ticks.max(1));
+
+            // The idle period is whole clock ticks, so its fraction of a tick
+            // is dropped. end_tickless counts the actual ticks that went by,
+            // so nothing is lost.
+            let reload = ((tick * idle_ticks) >> 32) - 1;
+
+            self.tickless.store(true, Ordering::Relaxed);
+            self.syst
+                .set_reload((reload as u32).min(SysTickCalibration::MAX_RELOAD));
+            self.syst.clear_current();
+
+            idle_ticks as u32 * self.tick_ms
+        })
+    }
+
+    /// Goes back to normal ticks after [`start_tickless`](#method.start_tickless),
+    /// adding the time spent idle to the count. Returns how many milliseconds
+    /// were added.
+    ///
+    /// The time is measured from SysTick itself, so it’s only accurate if this
+    /// is called before SysTick wraps a second time, which is to say no later
+    /// than twice the idle time.
+    pub fn end_tickless(&mut self) -> u32 {
+        critical::free(|_| {
+            if !self.tickless.load(Ordering::Relaxed) {
+                return 0;
+            }
+
+            let wrapped = syst_has_wrapped();
+            let reload = SYST::get_reload();
+            let mut ticks = u64::from(ticks_into_period(reload));
+
+            if wrapped {
+                ticks += u64::from(reload) + 1;
+            }
+
+            let ms = self.credit_clock_ticks(ticks);
+
+            // Restore the normal reload (and its dithering) and start a fresh
+            // tick.
+            self.dither.set(self.dither.tick());
+            self.syst.clear_current();
+            self.dither.restart();
+            self.tickless.store(false, Ordering::Relaxed);
+
+            #[cfg(not(armv6m))]
+            self.slip.resync(self.dither.running_reload());
+
+            #[cfg(feature = "stats")]
+            self.stats.resync();
+
+            ms
+        })
+    }
+
+    /// Starts using the DWT cycle counter to detect SysTick wraps that were
+    /// missed because `count` wasn’t called often enough. `mode` chooses
+    /// whether they’re only reported (see [`slippage`](#method.slippage)) or
+    /// also added back to the count.
+    ///
+    /// This enables the cycle counter, so it shouldn’t be reset or disabled
+    /// afterwards. Missed wraps can only be detected if `count` is called at
+    /// least once every 2³² cycles (about 36 seconds at 120MHz).
+    ///
+    /// Returns false, and doesn’t track anything, if this core has no cycle
+    /// counter, or if SysTick is using the external reference clock (since
+    /// we can’t convert between its ticks and cycles). Not available on
+    /// ARMv6-M (Cortex-M0 and M0+).
+    #[cfg(not(armv6m))]
+    pub fn track_slippage(&self, dcb: &mut DCB, dwt: &mut DWT, mode: SlippageMode) -> bool {
+        self.clock_source == SystClkSource::Core
+            && self
+                .slip
+                .enable(dcb, dwt, mode, self.dither.running_reload())
+    }
+
+    /// Returns how many milliseconds have been missed since slippage tracking
+    /// was started (or last reset).
+    #[cfg(not(armv6m))]
+    pub fn slippage(&self) -> TimeSlippage {
+        self.slip.slippage()
+    }
+
+    /// Resets the number of missed milliseconds reported by
+    /// [`slippage`](#method.slippage) to 0.
+    #[cfg(not(armv6m))]
+    pub fn reset_slippage(&self) {
+        self.slip.reset();
+    }
+
+    /// Returns statistics on how often SysTick has been polled, since it was
+    /// created (or the statistics were last reset).
+    ///
+    /// Needs the `stats` feature, which makes every poll a little slower.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> PollStats {
+        self.stats.stats()
+    }
+
+    /// Resets the statistics returned by [`stats`](#method.stats).
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&self) {
+        self.stats.reset();
     }
 }
 
@@ -149,34 +1308,125 @@ impl CountsMillis for PollingSysTick {
     /// value will not increment unless polled (this is so it can operate
     /// during critical sections when interrupts are disabled).
     fn count(&self) -> Wrapping<u32> {
-        // This is all unsafe because incrementing the internal count happens as
-        // a side effect of reading it. We’re ok with that, because we know that
-        // we have sole control over the SYST singleton, so we’re the only ones
-        // who will see the wrapping.
-        if unsafe { (*self.syst.get()).has_wrapped() } {
-            // Disabled interrupts because += is non-atomic.
-            cortex_m::interrupt::free(|_| unsafe {
-                (*self.counter.get()) += Wrapping(1);
-            });
+        // Incrementing the internal count happens as a side effect of reading
+        // it. We know that we have sole control over the SYST singleton, so
+        // we’re the only ones who will see the wrapping.
+        // While tickless, wraps are counted by end_tickless instead.
+        if self.tickless.load(Ordering::Relaxed) {
+            return self.counter.get();
         }
 
-        unsafe { *self.counter.get() }
+        let wrapped = syst_has_wrapped();
+
+        if wrapped {
+            self.on_wrap();
+        }
+
+        #[cfg(feature = "stats")]
+        self.stats.on_poll(wrapped);
+
+        self.counter.get()
+    }
+
+    /// Returns the tick length.
+    fn resolution_ms(&self) -> u32 {
+        self.tick_ms
     }
 }
 
-impl DelayMs<u32> for PollingSysTick {
-    fn delay_ms(&mut self, ms: u32) {
-        let mut count_down = MillisCountDown::new(self);
-        count_down.start_ms(ms);
-        nb::block!(count_down.wait()).unwrap();
+impl CountsMillis64 for PollingSysTick {
+    /// 64-bit version of [`count`](#method.count). Has the same requirement
+    /// that it be polled at least once per millisecond.
+    fn count64(&self) -> u64 {
+        self.count();
+        self.counter.get64()
+    }
+}
+
+impl CountsMicros for PollingSysTick {
+    /// Combines the millisecond count with how far SysTick has gotten through
+    /// the current millisecond.
+    ///
+    /// Has the same requirement as [`count`](#method.count) that it be polled
+    /// at least once per millisecond.
+    fn count_micros(&self) -> Wrapping<u32> {
+        micros_from_current(
+            || self.count(),
+            || self.dither.running_reload(),
+            self.tick_ms,
+        )
+    }
+}
+
+/// Error type for waits that can’t fail, such as
+/// [`MillisCountDown::wait_ms`](struct.MillisCountDown.html#method.wait_ms).
+///
+/// This is `void::Void` by default, or `core::convert::Infallible` with the
+/// `nb1` feature. The embedded-hal 0.2 `CountDown` implementations always use
+/// `Void`, since that trait requires it.
+pub type Never = NeverInner;
+
+#[cfg(feature = "nb1")]
+use core::convert::Infallible as NeverInner;
+#[cfg(not(feature = "nb1"))]
+use void::Void as NeverInner;
+
+/// Errors from [`MillisCountDown`](struct.MillisCountDown.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CountDownError {
+    /// The countdown was never started, or has already finished or been
+    /// cancelled.
+    NotStarted,
+    /// The duration was longer than
+    /// [`MAX_COUNTDOWN_MS`](constant.MAX_COUNTDOWN_MS.html).
+    TooLong,
+}
+
+/// Longest duration, in milliseconds, that a `MillisCountDown` can time:
+/// about 24.8 days.
+///
+/// Countdowns compare the count to their target in a way that survives the
+/// count wrapping around, which only works while the two are less than half
+/// of the `u32` range apart. For longer times, use
+/// [`MillisCountDown64`](struct.MillisCountDown64.html).
+pub const MAX_COUNTDOWN_MS: u32 = i32::MAX as u32;
+
+/// Converts a `Duration` to milliseconds, or `None` if it’s longer than
+/// `MAX_COUNTDOWN_MS`.
+pub(crate) fn checked_duration_ms(dur: Duration) -> Option<u32> {
+    let ms = dur
+        .as_secs()
+        .checked_mul(1000)?
+        .checked_add(u64::from(dur.subsec_millis()))?;
+
+    if ms <= u64::from(MAX_COUNTDOWN_MS) {
+        Some(ms as u32)
+    } else {
+        None
     }
 }
 
 /// `CountDown` that uses an underlying `CountsMillis` (probably
 /// `PollingSysTick`).
 pub struct MillisCountDown<'a, CM: CountsMillis> {
-    counter: &'a CM,
+    pub(crate) counter: &'a CM,
+    started_millis: Wrapping<u32>,
+    duration_ms: Option<u32>,
     target_millis: Option<Wrapping<u32>>,
+    max_lateness_ms: u32,
+}
+
+// Doesn’t read the counter, since that would update a PollingSysTick as a side
+// effect of printing.
+impl<'a, CM: CountsMillis> fmt::Debug for MillisCountDown<'a, CM> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MillisCountDown")
+            .field("deadline", &self.deadline())
+            .field("duration_ms", &self.duration_ms)
+            .field("max_lateness_ms", &self.max_lateness_ms)
+            .finish()
+    }
 }
 
 impl<'a, CM: CountsMillis> MillisCountDown<'a, CM> {
@@ -186,7 +1436,10 @@ impl<'a, CM: CountsMillis> MillisCountDown<'a, CM> {
     /// [`PollingSysTick`](struct.PollingSysTick.html).
     pub fn new(counter: &'a CM) -> Self {
         MillisCountDown {
+            started_millis: Wrapping(0),
+            duration_ms: None,
             target_millis: None,
+            max_lateness_ms: 0,
             counter,
         }
     }
@@ -196,54 +1449,408 @@ impl<'a, CM: CountsMillis> MillisCountDown<'a, CM> {
     ///
     /// Use this if you want to avoid the `u64`s in `Duration`.
     pub fn start_ms(&mut self, ms: u32) {
-        self.target_millis = Some(self.counter.count() + Wrapping(ms));
+        self.started_millis = self.counter.count();
+        self.duration_ms = Some(ms);
+        self.target_millis = Some(self.started_millis + Wrapping(ms));
+    }
+
+    /// Like [`start_ms`](#method.start_ms), but guarantees that at least `ms`
+    /// milliseconds pass before the countdown finishes, even on a counter
+    /// that counts in steps of more than 1ms.
+    ///
+    /// A countdown can start anywhere within the counter’s current tick, so
+    /// with 10ms ticks `start_ms(1)` might finish almost right away, at the
+    /// next tick. This adds up to a tick to the countdown to make up for it.
+    /// On counters with 1ms resolution it’s the same as `start_ms`.
+    pub fn start_ms_at_least(&mut self, ms: u32) {
+        let padding = self.counter.resolution_ms().saturating_sub(1);
+        self.start_ms(ms.saturating_add(padding));
+    }
+
+    /// Starts timing `duration`, or returns `CountDownError::TooLong` if it’s
+    /// longer than [`MAX_COUNTDOWN_MS`](constant.MAX_COUNTDOWN_MS.html).
+    ///
+    /// `CountDown`’s `start` can’t return an error, so it shortens durations
+    /// that are too long to `MAX_COUNTDOWN_MS` instead.
+    pub fn try_start(&mut self, duration: Duration) -> Result<(), CountDownError> {
+        let ms = checked_duration_ms(duration).ok_or(CountDownError::TooLong)?;
+        self.start_ms(ms);
+        Ok(())
+    }
+
+    /// Starts the countdown so that it finishes once the count is past
+    /// `deadline`.
+    ///
+    /// `start_until(Instant::now(counter) + ms)` behaves the same as
+    /// `start_ms(ms)`. Use this to time several countdowns against a deadline
+    /// they share.
+    pub fn start_until(&mut self, deadline: Instant) {
+        self.started_millis = self.counter.count();
+        self.duration_ms = Some((Wrapping(deadline.millis()) - self.started_millis).0);
+        self.target_millis = Some(Wrapping(deadline.millis()));
+    }
+
+    /// Returns the `Instant` that the countdown will finish after, or `None` if
+    /// it isn’t running.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.target_millis
+            .map(|target| Instant::from_millis(target.0))
+    }
+
+    /// Version of `CountDown`’s `start` that takes a fugit `Duration`.
+    ///
+    /// fugit durations can be passed to `start` as well, but that converts
+    /// them to `core::time::Duration` first. This method does the conversion
+    /// to milliseconds entirely in `u32`s, which is much cheaper on
+    /// Cortex-M0.
+    #[cfg(feature = "fugit")]
+    pub fn start_duration<const NOM: u32, const DENOM: u32>(
+        &mut self,
+        duration: fugit::Duration<u32, NOM, DENOM>,
+    ) {
+        self.start_ms(duration.to_millis());
     }
 
     /// Underlying implementation of `CountDown`’s `wait` that works directly on
     /// our underlying u32 ms values and can be used by any `CountDown` trait
     /// implementations.
     ///
-    /// Calling this method before `start`, or after it has already returned
-    /// `Ok` will panic.
-    pub fn wait_ms(&mut self) -> Result<(), nb::Error<Void>> {
-        // Rollover-safe duration check derived from:
-        // https://playground.arduino.cc/Code/TimingRollover/
-        if (self.counter.count() - self.target_millis.unwrap()).0 as i32 > 0 {
+    /// Returns
+    /// [`nb::Error::Other(CountDownError::NotStarted)`](enum.CountDownError.html)
+    /// if called before `start`, or after it has already returned `Ok`.
+    pub fn try_wait_ms(&mut self) -> Result<(), nb::Error<CountDownError>> {
+        self.wait_with_lateness().map(|_| ())
+    }
+
+    /// Version of [`try_wait_ms`](#method.try_wait_ms) that returns how late
+    /// the countdown was noticed, in milliseconds.
+    ///
+    /// This is 0 if it was noticed at the first count past the deadline. More
+    /// than that means the loop polling it fell behind.
+    pub fn wait_with_lateness(&mut self) -> nb::Result<u32, CountDownError> {
+        let target = self.target_millis.ok_or(CountDownError::NotStarted)?;
+        let now = self.counter.count();
+
+        if has_passed(now, target) {
             self.target_millis.take();
-            Ok(())
+
+            let lateness = (now - target).0 - 1;
+            self.max_lateness_ms = self.max_lateness_ms.max(lateness);
+
+            Ok(lateness)
         } else {
             Err(nb::Error::WouldBlock)
         }
     }
-}
 
-impl<'a, CM: CountsMillis> CountDown for MillisCountDown<'a, CM> {
-    type Time = Duration;
+    /// Returns the most that this countdown has been late, out of every time
+    /// it has finished, as reported by
+    /// [`wait_with_lateness`](#method.wait_with_lateness).
+    pub fn max_lateness_ms(&self) -> u32 {
+        self.max_lateness_ms
+    }
+
+    /// Resets [`max_lateness_ms`](#method.max_lateness_ms) to 0.
+    pub fn reset_max_lateness(&mut self) {
+        self.max_lateness_ms = 0;
+    }
 
-    /// Starts timing the given `Duration`.
+    /// Version of [`try_wait_ms`](#method.try_wait_ms) that can’t fail, like
+    /// `CountDown`’s `wait`.
     ///
-    /// [`wait`](#method.wait) will return
-    /// [`nb::Error::WouldBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
-    /// until this amount of time has passed.
+    /// Calling this method before `start`, or after it has already returned
+    /// `Ok`, returns `Ok` immediately, since there’s nothing to wait for.
+    pub fn wait_ms(&mut self) -> Result<(), nb::Error<Never>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+
+    /// Starts the countdown over from now, with the duration that was last
+    /// passed to `start_ms`. Works whether or not the countdown is still
+    /// running.
     ///
-    /// Calling this method before the time has fully ellapsed will reset the
-    /// timer.
-    fn start<T>(&mut self, count: T)
-    where
-        T: Into<Self::Time>,
-    {
-        let dur: Self::Time = count.into();
-        let millis = (dur.as_secs() as u32) * 1000 + dur.subsec_millis() as u32;
-        self.start_ms(millis);
+    /// Any changes from [`extend_ms`](#method.extend_ms) or
+    /// [`shorten_ms`](#method.shorten_ms) are not kept.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown has never been
+    /// started.
+    pub fn restart(&mut self) -> Result<(), CountDownError> {
+        let ms = self.duration_ms.ok_or(CountDownError::NotStarted)?;
+        self.start_ms(ms);
+        Ok(())
     }
 
-    /// Returns
-    /// [`nb::Error::WillBlock`](https://docs.rs/nb/0.1.2/nb/enum.Error.html#variant.WouldBlock)
-    /// while the timer runs, then will return `Result::Ok`.
+    /// Pushes the end of a running countdown `ms` milliseconds later.
+    ///
+    /// As with `start_ms`, the total time remaining must stay under about 24.8
+    /// days.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown isn’t running.
+    pub fn extend_ms(&mut self, ms: u32) -> Result<(), CountDownError> {
+        let target = self
+            .target_millis
+            .as_mut()
+            .ok_or(CountDownError::NotStarted)?;
+        *target += Wrapping(ms);
+        Ok(())
+    }
+
+    /// Pulls the end of a running countdown `ms` milliseconds earlier. If
+    /// there’s less than `ms` remaining, the countdown will finish the next
+    /// time it’s waited on.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown isn’t running.
+    pub fn shorten_ms(&mut self, ms: u32) -> Result<(), CountDownError> {
+        let target = self.target_millis.ok_or(CountDownError::NotStarted)?;
+        let now = self.counter.count();
+
+        self.target_millis = Some(if has_passed(now, target) || ms >= (target - now).0 {
+            now - Wrapping(1)
+        } else {
+            target - Wrapping(ms)
+        });
+
+        Ok(())
+    }
+
+    /// Returns true if the countdown has been started and `wait_ms` hasn’t
+    /// returned `Ok` for it yet.
+    ///
+    /// Note that this can be true even after the time is up, if `wait_ms`
+    /// hasn’t been called since.
+    pub fn is_running(&self) -> bool {
+        self.target_millis.is_some()
+    }
+
+    /// Returns how many milliseconds are left until the countdown finishes,
+    /// or `None` if it isn’t running.
+    ///
+    /// Returns `Some(0)` once the time is up, even if `wait_ms` hasn’t been
+    /// called to notice it yet.
+    pub fn remaining_ms(&self) -> Option<u32> {
+        let target = self.target_millis?;
+        let now = self.counter.count();
+
+        if has_passed(now, target) {
+            Some(0)
+        } else {
+            Some((target - now).0)
+        }
+    }
+
+    /// Returns how many milliseconds it has been since the countdown was
+    /// started, or `None` if it isn’t running.
+    pub fn elapsed_ms(&self) -> Option<u32> {
+        self.target_millis
+            .map(|_| (self.counter.count() - self.started_millis).0)
+    }
+
+    /// Stops the countdown. Afterwards, it can be started again with
+    /// `start_ms`.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown wasn’t running.
+    pub fn cancel(&mut self) -> Result<(), CountDownError> {
+        self.target_millis
+            .take()
+            .map(|_| ())
+            .ok_or(CountDownError::NotStarted)
+    }
+}
+
+/// `CountDown` that uses an underlying `CountsMillis64`, for intervals too long
+/// for [`MillisCountDown`](struct.MillisCountDown.html).
+///
+/// Since its count won’t wrap, this doesn’t have `MillisCountDown`’s limit of
+/// about 24.8 days.
+pub struct MillisCountDown64<'a, CM: CountsMillis64> {
+    counter: &'a CM,
+    target_millis: Option<u64>,
+}
+
+impl<'a, CM: CountsMillis64> fmt::Debug for MillisCountDown64<'a, CM> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MillisCountDown64")
+            .field("target_millis", &self.target_millis)
+            .finish()
+    }
+}
+
+impl<'a, CM: CountsMillis64> MillisCountDown64<'a, CM> {
+    /// Creates a `MillisCountDown64` from a `CountsMillis64` source.
+    pub fn new(counter: &'a CM) -> Self {
+        MillisCountDown64 {
+            target_millis: None,
+            counter,
+        }
+    }
+
+    /// Underlying version of `CountDown`’s `start` that takes a `u64` of
+    /// milliseconds rather than a `Duration`.
+    pub fn start_ms(&mut self, ms: u64) {
+        self.target_millis = Some(self.counter.count64().saturating_add(ms));
+    }
+
+    /// Underlying implementation of `CountDown`’s `wait`.
+    ///
+    /// Returns `nb::Error::Other(CountDownError::NotStarted)` if called before
+    /// `start`, or after it has already returned `Ok`.
+    pub fn try_wait_ms(&mut self) -> Result<(), nb::Error<CountDownError>> {
+        let target = self.target_millis.ok_or(CountDownError::NotStarted)?;
+
+        if self.counter.count64() > target {
+            self.target_millis.take();
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Version of [`try_wait_ms`](#method.try_wait_ms) that can’t fail, like
+    /// `CountDown`’s `wait`.
     ///
     /// Calling this method before `start`, or after it has already returned
-    /// `Ok` will panic.
-    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
-        self.wait_ms()
+    /// `Ok`, returns `Ok` immediately.
+    pub fn wait_ms(&mut self) -> Result<(), nb::Error<Never>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+
+    /// Stops the countdown.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown wasn’t running.
+    pub fn cancel(&mut self) -> Result<(), CountDownError> {
+        self.target_millis
+            .take()
+            .map(|_| ())
+            .ok_or(CountDownError::NotStarted)
+    }
+}
+
+/// Returns true if `now` is after `target`, as long as the two are within about
+/// 24.8 days (`i32::MAX` milliseconds) of each other.
+pub(crate) fn has_passed(now: Wrapping<u32>, target: Wrapping<u32>) -> bool {
+    // Rollover-safe duration check derived from:
+    // https://playground.arduino.cc/Code/TimingRollover/
+    (now - target).0 as i32 > 0
+}
+
+const SYST_CSR_COUNTFLAG: u32 = 1 << 16;
+const SYST_CSR_TICKINT: u32 = 1 << 1;
+
+/// Returns true if SysTick has wrapped since the last time this was called.
+///
+/// This is `SYST::has_wrapped` without needing a `&mut SYST`. Reading the
+/// control and status register clears COUNTFLAG in the same access, so if more
+/// than one context calls this at once, only one of them will see a given wrap.
+pub(crate) fn syst_has_wrapped() -> bool {
+    unsafe { (*SYST::PTR).csr.read() & SYST_CSR_COUNTFLAG != 0 }
+}
+
+/// Combines a millisecond count with SysTick’s current value to get a count of
+/// microseconds.
+///
+/// `count` is called before and after reading the current value. If SysTick
+/// wrapped in between, then we can’t tell which tick the current value belongs
+/// to, so we try again. `reload` returns the reload value of the period
+/// underway, and `tick_ms` is how many milliseconds SysTick takes to wrap.
+pub(crate) fn micros_from_current(
+    count: impl Fn() -> Wrapping<u32>,
+    reload: impl Fn() -> u32,
+    tick_ms: u32,
+) -> Wrapping<u32> {
+    loop {
+        let millis = count();
+        let ticks_in = ticks_into_period(reload());
+
+        if count() == millis {
+            let reload = reload();
+
+            // SysTick counts down, so the ticks into the current tick are how
+            // far it is below the reload value. The reload value is only 24
+            // bits, so this can’t overflow a u64, and ticks_into_period
+            // saturates, so it’s never more than a whole tick.
+            let micros = u64::from(ticks_in) * u64::from(tick_ms) * 1000 / (u64::from(reload) + 1);
+
+            return millis * Wrapping(1000) + Wrapping(micros as u32);
+        }
+    }
+}
+
+/// Converts the result of a `try_wait_ms` into one for `wait_ms` or
+/// `CountDown`’s `wait`, which can’t return errors. A countdown that isn’t
+/// running has nothing to wait for, so it counts as finished.
+///
+/// Generic over the error type so that the embedded-hal 0.2 `wait`s can get
+/// `Void` even when `Never` is `Infallible`.
+pub(crate) fn ignore_not_started<E>(
+    result: Result<(), nb::Error<CountDownError>>,
+) -> Result<(), nb::Error<E>> {
+    match result {
+        // Waiting can only fail with NotStarted.
+        Ok(()) | Err(nb::Error::Other(_)) => Ok(()),
+        Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+    }
+}
+
+/// Blocks until at least `ms` milliseconds have passed on `counter`.
+///
+/// Shared by the `DelayMs` implementations of all of the counters.
+#[cfg(any(feature = "eh0", feature = "eh1", feature = "fugit-timer"))]
+pub(crate) fn delay_ms<CM: CountsMillis>(counter: &CM, ms: u32) {
+    for_each_part(counter, ms, |count_down| {
+        nb::block!(count_down.wait_ms()).unwrap();
+    });
+}
+
+/// Sleeps with `wfi` until at least `ms` milliseconds have passed on
+/// `counter`. Something (like the SysTick exception) has to wake the processor
+/// each tick.
+pub(crate) fn sleep_ms<CM: CountsMillis>(counter: &CM, ms: u32) {
+    for_each_part(counter, ms, |count_down| {
+        // If SysTick wraps between checking and sleeping, we sleep for one
+        // more tick than we needed to. That’s fine for a delay that only
+        // promises “at least.”
+        while count_down.wait_ms().is_err() {
+            cortex_m::asm::wfi();
+        }
+    });
+}
+
+/// Starts a countdown for each part of `ms`, one after another, and calls
+/// `wait` to wait it out.
+///
+/// A countdown longer than `MAX_COUNTDOWN_MS` would look like it had already
+/// finished, so longer delays are split up. Parts are half that, leaving room
+/// for `start_ms_at_least` to pad them.
+fn for_each_part<CM: CountsMillis>(
+    counter: &CM,
+    ms: u32,
+    mut wait: impl FnMut(&mut MillisCountDown<'_, CM>),
+) {
+    let mut remaining = ms;
+
+    loop {
+        let part = remaining.min(MAX_COUNTDOWN_MS / 2);
+
+        let mut count_down = MillisCountDown::new(counter);
+        count_down.start_ms_at_least(part);
+        wait(&mut count_down);
+
+        remaining -= part;
+
+        if remaining == 0 {
+            break;
+        }
+    }
+}
+
+/// Blocks until at least `us` microseconds have passed on `counter`.
+#[cfg(any(feature = "eh0", feature = "eh1"))]
+pub(crate) fn delay_us<CM: CountsMicros>(counter: &CM, us: u32) {
+    if us == 0 {
+        return;
     }
+
+    // count_micros rounds down, so `start` may already be most of the way
+    // through its microsecond. Wait for one more than asked to make up for it.
+    let start = counter.count_micros();
+    while (counter.count_micros() - start).0 < us.saturating_add(1) {}
 }
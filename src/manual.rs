@@ -0,0 +1,86 @@
+use core::num::Wrapping;
+
+use crate::counter::Counter64;
+use crate::{CountsMillis, CountsMillis64};
+
+/// Millisecond counter advanced by your own tick handler
+///
+/// Use this when something else already owns SysTick, such as an RTOS or your
+/// HAL. Call [`tick`](#method.tick) (or [`add_ms`](#method.add_ms), if your
+/// tick is slower than 1ms) from that handler, and then use this anywhere a
+/// [`CountsMillis`](trait.CountsMillis.html) is needed, including as the
+/// source for [`MillisCountDown`](struct.MillisCountDown.html) instances.
+///
+/// ## Usage
+///
+/// `new` is `const`, so this can be a `static` that your tick hook and the
+/// rest of your program share:
+///
+/// ```ignore
+/// static TICKS: ManualTickCounter = ManualTickCounter::new();
+///
+/// #[no_mangle]
+/// extern "C" fn vApplicationTickHook() {
+///     TICKS.tick();
+/// }
+///
+/// let mut timeout = MillisCountDown::new(&TICKS);
+/// ```
+///
+/// The count only advances when you tell it to, so blocking delays on it will
+/// never finish if the tick handler can’t run (for example, with interrupts
+/// disabled).
+pub struct ManualTickCounter {
+    counter: Counter64,
+}
+
+impl ManualTickCounter {
+    /// Creates a counter starting at 0.
+    pub const fn new() -> Self {
+        ManualTickCounter::starting_at(0)
+    }
+
+    /// Creates a counter starting at `ms`.
+    ///
+    /// Starting just short of the `u32` count wrapping, such as at
+    /// `u32::MAX as u64 - 10`, is a quick way to check that timeouts work
+    /// across the wrap without waiting 49 days for it.
+    pub const fn starting_at(ms: u64) -> Self {
+        ManualTickCounter {
+            counter: Counter64::starting_at(ms),
+        }
+    }
+
+    /// Advances the count by one millisecond.
+    ///
+    /// Safe to call from any context, including at the same time as `count`
+    /// or other calls to `tick`.
+    pub fn tick(&self) {
+        self.counter.add(1);
+    }
+
+    /// Advances the count by `ms` milliseconds, for tick handlers that run
+    /// less often than once per millisecond.
+    pub fn add_ms(&self, ms: u32) {
+        self.counter.add(ms);
+    }
+}
+
+impl Default for ManualTickCounter {
+    fn default() -> Self {
+        ManualTickCounter::new()
+    }
+}
+
+impl CountsMillis for ManualTickCounter {
+    /// Returns the number of milliseconds that have been ticked.
+    fn count(&self) -> Wrapping<u32> {
+        self.counter.get()
+    }
+}
+
+impl CountsMillis64 for ManualTickCounter {
+    fn count64(&self) -> u64 {
+        self.counter.get64()
+    }
+}
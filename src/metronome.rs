@@ -0,0 +1,94 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Never};
+
+/// What a [`Metronome`](struct.Metronome.html) does about ticks that it
+/// missed because it wasn’t polled in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CatchUp {
+    /// Fire once for each missed tick, back to back, until caught up.
+    Burst,
+    /// Fire once, then skip ahead to the next tick that hasn’t happened yet.
+    Skip,
+}
+
+/// Fires at a fixed rate, on a grid of ticks measured from when it started
+///
+/// Restarting a `MillisCountDown` each time it finishes drifts later by
+/// however long it took to notice. Ticks from a `Metronome` are always
+/// `start + n * period_ms`, so a control loop driven by it keeps its rate over
+/// any length of time.
+///
+/// ```ignore
+/// let mut metronome = Metronome::new(&systick, 10, CatchUp::Skip);
+///
+/// loop {
+///     nb::block!(metronome.poll_tick()).unwrap();
+///     run_control_loop();
+/// }
+/// ```
+pub struct Metronome<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    period_ms: u32,
+    catch_up: CatchUp,
+    next_tick: Wrapping<u32>,
+    ticks: u32,
+    skipped: u32,
+}
+
+impl<'a, CM: CountsMillis> Metronome<'a, CM> {
+    /// Creates a `Metronome` whose first tick is `period_ms` from now.
+    pub fn new(counter: &'a CM, period_ms: u32, catch_up: CatchUp) -> Self {
+        Metronome {
+            counter,
+            period_ms,
+            catch_up,
+            next_tick: counter.count() + Wrapping(period_ms),
+            ticks: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Returns `Ok` if a tick is due, or `nb::Error::WouldBlock` if not.
+    pub fn poll_tick(&mut self) -> nb::Result<(), Never> {
+        let now = self.counter.count();
+
+        if !has_passed(now, self.next_tick) {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let mut advance = 1;
+
+        if self.catch_up == CatchUp::Skip && self.period_ms > 0 {
+            // Ticks strictly before now, besides the one we’re firing for
+            let missed = (now - self.next_tick).0.saturating_sub(1) / self.period_ms;
+            advance += missed;
+            self.skipped = self.skipped.wrapping_add(missed);
+        }
+
+        self.next_tick += Wrapping(self.period_ms.wrapping_mul(advance));
+        self.ticks = self.ticks.wrapping_add(advance);
+
+        Ok(())
+    }
+
+    /// Returns how many ticks have happened since the `Metronome` was started,
+    /// including skipped ones.
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Returns how many ticks have been skipped by
+    /// [`CatchUp::Skip`](enum.CatchUp.html#variant.Skip).
+    pub fn skipped(&self) -> u32 {
+        self.skipped
+    }
+
+    /// Starts the grid of ticks over from now.
+    pub fn restart(&mut self) {
+        self.next_tick = self.counter.count() + Wrapping(self.period_ms);
+        self.ticks = 0;
+        self.skipped = 0;
+    }
+}
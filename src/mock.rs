@@ -0,0 +1,97 @@
+use core::cell::Cell;
+use core::num::Wrapping;
+
+use crate::{CountsMillis, CountsMillis64};
+
+/// Counter for unit tests, whose time only moves when you say so
+///
+/// Write your timeouts and delays against
+/// [`CountsMillis`](trait.CountsMillis.html), and then test them on the host
+/// with a `MockMillis` in place of the real SysTick. Nothing here touches any
+/// hardware.
+///
+/// ```ignore
+/// let clock = MockMillis::new();
+/// let mut timeout = MillisCountDown::new(&clock);
+/// timeout.start_ms(100);
+///
+/// clock.advance_ms(99);
+/// assert!(timeout.wait_ms().is_err());
+///
+/// clock.advance_ms(2);
+/// assert!(timeout.wait_ms().is_ok());
+/// ```
+///
+/// Code that blocks until time has passed will spin forever unless something
+/// advances the clock, so for that use
+/// [`auto_advance_ms`](#method.auto_advance_ms) to have every read move time
+/// forward.
+///
+/// It isn’t `Sync`, and doesn’t use critical sections, so it works on the host
+/// where `cortex_m::interrupt::free` doesn’t.
+pub struct MockMillis {
+    ms: Cell<u64>,
+    step_ms: Cell<u32>,
+}
+
+impl MockMillis {
+    /// Creates a counter starting at 0.
+    pub const fn new() -> Self {
+        MockMillis {
+            ms: Cell::new(0),
+            step_ms: Cell::new(0),
+        }
+    }
+
+    /// Creates a counter starting at `ms`, such as just before the `u32`
+    /// count wraps.
+    pub fn starting_at(ms: u64) -> Self {
+        let mock = MockMillis::new();
+        mock.set_ms(ms);
+        mock
+    }
+
+    /// Moves time forward by `ms` milliseconds.
+    pub fn advance_ms(&self, ms: u32) {
+        self.ms.set(self.ms.get() + u64::from(ms));
+    }
+
+    /// Sets the time to `ms` milliseconds.
+    pub fn set_ms(&self, ms: u64) {
+        self.ms.set(ms);
+    }
+
+    /// Returns the current time, without moving it forward like `count` does
+    /// with [`auto_advance_ms`](#method.auto_advance_ms).
+    pub fn now_ms(&self) -> u64 {
+        self.ms.get()
+    }
+
+    /// Moves time forward by `step_ms` every time the count is read, or stops
+    /// doing that if `step_ms` is 0 (the default).
+    pub fn auto_advance_ms(&self, step_ms: u32) {
+        self.step_ms.set(step_ms);
+    }
+}
+
+impl Default for MockMillis {
+    fn default() -> Self {
+        MockMillis::new()
+    }
+}
+
+impl CountsMillis for MockMillis {
+    /// Returns the current mock time, then moves it forward by the
+    /// [`auto_advance_ms`](#method.auto_advance_ms) step.
+    fn count(&self) -> Wrapping<u32> {
+        Wrapping(self.count64() as u32)
+    }
+}
+
+impl CountsMillis64 for MockMillis {
+    fn count64(&self) -> u64 {
+        let now = self.ms.get();
+        self.advance_ms(self.step_ms.get());
+        now
+    }
+}
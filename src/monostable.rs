@@ -0,0 +1,107 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Output};
+
+/// Switches an output on for a fixed time after each trigger
+///
+/// A one-shot pulse for buzzer chirps, relay pulses and status LEDs: call
+/// [`trigger`](#method.trigger) to switch the output on, and
+/// [`poll`](#method.poll) often from your main loop to switch it off again
+/// once `pulse_ms` milliseconds have passed.
+///
+/// ```ignore
+/// let mut chirp = Monostable::new(&systick, 50, PinOutput(buzzer));
+///
+/// loop {
+///     if button.update(read_button()) && button.rising_edge() {
+///         chirp.trigger();
+///     }
+///
+///     chirp.poll();
+/// }
+/// ```
+///
+/// By default, triggering while the pulse is already on does nothing, so the
+/// pulse always ends `pulse_ms` after it started. A
+/// [`retriggerable`](#method.retriggerable) one restarts the pulse instead, so
+/// that the output stays on until `pulse_ms` after the last trigger.
+pub struct Monostable<'a, CM: CountsMillis, O: Output> {
+    counter: &'a CM,
+    output: O,
+    pulse_ms: u32,
+    retriggerable: bool,
+    /// When the current pulse started, or `None` if the output is off.
+    started: Option<Wrapping<u32>>,
+}
+
+impl<'a, CM: CountsMillis, O: Output> Monostable<'a, CM, O> {
+    /// Makes pulses `pulse_ms` long on `output`, timed with `counter`.
+    /// Switches `output` off.
+    pub fn new(counter: &'a CM, pulse_ms: u32, mut output: O) -> Self {
+        output.set(false);
+
+        Monostable {
+            counter,
+            output,
+            pulse_ms,
+            retriggerable: false,
+            started: None,
+        }
+    }
+
+    /// Makes triggering during a pulse restart it.
+    pub fn retriggerable(mut self) -> Self {
+        self.retriggerable = true;
+        self
+    }
+
+    /// Changes how long pulses are, starting with the current one.
+    pub fn set_pulse_ms(&mut self, pulse_ms: u32) {
+        self.pulse_ms = pulse_ms;
+    }
+
+    /// Switches the output on and starts the pulse. Returns false if it was
+    /// ignored because a pulse was already on and this isn’t retriggerable.
+    pub fn trigger(&mut self) -> bool {
+        if self.started.is_some() && !self.retriggerable {
+            return false;
+        }
+
+        if self.started.is_none() {
+            self.output.set(true);
+        }
+
+        self.started = Some(self.counter.count());
+        true
+    }
+
+    /// Switches the output off if the pulse is over. Returns true while the
+    /// output is on.
+    pub fn poll(&mut self) -> bool {
+        if let Some(started) = self.started {
+            if has_passed(self.counter.count(), started + Wrapping(self.pulse_ms)) {
+                self.cancel();
+            }
+        }
+
+        self.is_on()
+    }
+
+    /// Returns true while a pulse is on.
+    pub fn is_on(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Ends the pulse early, switching the output off.
+    pub fn cancel(&mut self) {
+        if self.started.take().is_some() {
+            self.output.set(false);
+        }
+    }
+
+    /// Returns the output, switched off.
+    pub fn free(mut self) -> O {
+        self.cancel();
+        self.output
+    }
+}
@@ -0,0 +1,34 @@
+use cortex_m::peripheral::syst::SystClkSource;
+
+use crate::{CalibrationError, PollingSysTick, SysTickCalibration};
+
+/// Something that needs to know when the core clock changes speed
+///
+/// Call [`clock_changed`](#tymethod.clock_changed) from wherever you
+/// reconfigure your clocks, or have your HAL’s clock manager call it, so
+/// that timing stays right at the new speed.
+///
+/// ```ignore
+/// clocks.set_sysclk(120.MHz());
+/// systick.clock_changed(120_000_000)?;
+/// ```
+pub trait ClockObserver {
+    /// Tells the observer that the core clock is now running at `new_hz`.
+    fn clock_changed(&mut self, new_hz: u32) -> Result<(), CalibrationError>;
+}
+
+impl ClockObserver for PollingSysTick {
+    /// Recalibrates for the new clock speed with
+    /// [`set_calibration`](struct.PollingSysTick.html#method.set_calibration),
+    /// keeping the count and the time already spent in the current tick.
+    ///
+    /// Does nothing if SysTick runs from the external reference clock, since
+    /// that doesn’t change with the core clock.
+    fn clock_changed(&mut self, new_hz: u32) -> Result<(), CalibrationError> {
+        if self.clock_source == SystClkSource::External {
+            return Ok(());
+        }
+
+        self.set_calibration(&SysTickCalibration::from_clock_hz(new_hz)?)
+    }
+}
@@ -0,0 +1,37 @@
+/// Something that can be switched on and off, for helpers like
+/// [`Monostable`](struct.Monostable.html) that drive an output
+///
+/// Implemented for any `FnMut(bool)` closure, which is passed `true` to switch
+/// on and `false` to switch off. With the `eh0` feature, wrap an embedded-hal
+/// `OutputPin` in a [`PinOutput`](struct.PinOutput.html) to use it directly.
+pub trait Output {
+    /// Switches the output on or off.
+    fn set(&mut self, on: bool);
+}
+
+impl<F: FnMut(bool)> Output for F {
+    fn set(&mut self, on: bool) {
+        self(on);
+    }
+}
+
+/// [`Output`](trait.Output.html) for an embedded-hal 0.2 `OutputPin`, with
+/// on being high
+///
+/// Errors from the pin are ignored, since most HALs’ pins can’t fail. For an
+/// active-low output, such as an LED wired to the supply, use a closure
+/// instead.
+#[cfg(feature = "eh0")]
+#[derive(Debug)]
+pub struct PinOutput<P>(pub P);
+
+#[cfg(feature = "eh0")]
+impl<P: embedded_hal::digital::v2::OutputPin> Output for PinOutput<P> {
+    fn set(&mut self, on: bool) {
+        let _ = if on {
+            self.0.set_high()
+        } else {
+            self.0.set_low()
+        };
+    }
+}
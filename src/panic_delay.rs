@@ -0,0 +1,52 @@
+use cortex_m::peripheral::syst::SystClkSource;
+
+use crate::SysTickCalibration;
+
+/// Blocks for at least `ms` milliseconds, using SysTick directly, from a
+/// panic or fault handler.
+///
+/// When a handler needs to blink an LED or wait before resetting, whatever
+/// owns SysTick might be in the middle of using it, or might not exist at all.
+/// This steals SysTick and sets it up from scratch: 1ms ticks from the core
+/// clock, with the exception disabled, rounding each tick up so the delay is
+/// never short. It doesn’t use critical sections or interrupts, and nothing
+/// it touches needs to have been initialized.
+///
+/// SysTick is left running with this configuration afterwards.
+///
+/// ```ignore
+/// #[panic_handler]
+/// fn panic(_: &PanicInfo) -> ! {
+///     loop {
+///         led.toggle();
+///         unsafe { panic_delay_ms(&CALIBRATION, 250) };
+///     }
+/// }
+/// ```
+///
+/// # Safety
+///
+/// Any `PollingSysTick` or other user of SysTick must never be used again
+/// afterwards, since SysTick is reconfigured and its count is lost.
+pub unsafe fn panic_delay_ms(calibration: &SysTickCalibration, ms: u32) {
+    let mut syst = cortex_m::Peripherals::steal().SYST;
+    let tick = calibration.reload_for_ms();
+    let reload = if tick.fraction > 0 {
+        tick.reload + 1
+    } else {
+        tick.reload
+    };
+
+    syst.disable_counter();
+    syst.disable_interrupt();
+    syst.set_clock_source(SystClkSource::Core);
+    syst.set_reload(reload.min(SysTickCalibration::MAX_RELOAD));
+    syst.clear_current();
+    syst.enable_counter();
+
+    // clear_current also cleared COUNTFLAG, so each wrap from here on is a
+    // whole millisecond.
+    for _ in 0..ms {
+        while !syst.has_wrapped() {}
+    }
+}
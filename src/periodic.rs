@@ -0,0 +1,76 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, ignore_not_started, CountDownError, CountsMillis, Never};
+
+/// `CountDown` that restarts itself each time it finishes.
+///
+/// Each new period is timed from the end of the previous one, rather than from
+/// when [`wait_ms`](#method.wait_ms) noticed that it had ended, so a loop that
+/// waits on this will run at a fixed rate without drifting by however long the
+/// loop body takes.
+///
+/// If the loop falls more than a period behind, `wait_ms` will return `Ok`
+/// immediately for each missed period until it catches up.
+pub struct PeriodicMillisCountDown<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    period_ms: u32,
+    target_millis: Option<Wrapping<u32>>,
+}
+
+impl<'a, CM: CountsMillis> PeriodicMillisCountDown<'a, CM> {
+    /// Creates a `PeriodicMillisCountDown` from a `CountsMillis` source.
+    pub fn new(counter: &'a CM) -> Self {
+        PeriodicMillisCountDown {
+            counter,
+            period_ms: 0,
+            target_millis: None,
+        }
+    }
+
+    /// Underlying version of `CountDown`’s `start` that takes a `u32` of
+    /// milliseconds rather than a `Duration`.
+    ///
+    /// The first period is timed from now. A `period_ms` of 0 is treated as
+    /// 1, the shortest period the count can measure, rather than finishing on
+    /// every call to `wait`.
+    pub fn start_ms(&mut self, period_ms: u32) {
+        let period_ms = period_ms.max(1);
+
+        self.period_ms = period_ms;
+        self.target_millis = Some(self.counter.count() + Wrapping(period_ms));
+    }
+
+    /// Underlying implementation of `CountDown`’s `wait`. Returns `Ok` once
+    /// per period.
+    ///
+    /// Returns `nb::Error::Other(CountDownError::NotStarted)` if called before
+    /// `start` or after `cancel`.
+    pub fn try_wait_ms(&mut self) -> Result<(), nb::Error<CountDownError>> {
+        let target = self.target_millis.ok_or(CountDownError::NotStarted)?;
+
+        if has_passed(self.counter.count(), target) {
+            self.target_millis = Some(target + Wrapping(self.period_ms));
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Version of [`try_wait_ms`](#method.try_wait_ms) that can’t fail, like
+    /// `CountDown`’s `wait`.
+    ///
+    /// Calling this method before `start` returns `Ok` immediately.
+    pub fn wait_ms(&mut self) -> Result<(), nb::Error<Never>> {
+        ignore_not_started(self.try_wait_ms())
+    }
+
+    /// Stops the countdown.
+    ///
+    /// Returns `CountDownError::NotStarted` if the countdown wasn’t running.
+    pub fn cancel(&mut self) -> Result<(), CountDownError> {
+        self.target_millis
+            .take()
+            .map(|_| ())
+            .ok_or(CountDownError::NotStarted)
+    }
+}
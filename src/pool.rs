@@ -0,0 +1,165 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis};
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Free,
+    Stopped,
+    Running(Wrapping<u32>),
+}
+
+/// Handle to a countdown allocated from a
+/// [`CountDownPool`](struct.CountDownPool.html).
+///
+/// Just an index into the pool, so it’s `Copy` and has no lifetime to thread
+/// through the structs that hold it. Along with the index it records which
+/// allocation of that slot it’s for, so a copy kept after [`release`](struct.CountDownPool.html#method.release)
+/// stops working rather than controlling whoever is given the slot next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PoolTimer {
+    index: usize,
+    generation: u32,
+}
+
+/// Errors from [`CountDownPool`](struct.CountDownPool.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CountDownPoolError {
+    /// All `N` countdowns are already allocated.
+    Full,
+}
+
+/// Fixed set of `N` countdowns that owns its counter
+///
+/// A `MillisCountDown` borrows its counter, so a driver that keeps one has to
+/// carry that lifetime around. Instead, a pool can own the `PollingSysTick`
+/// (or any other counter) and hand out [`PoolTimer`](struct.PoolTimer.html)
+/// handles, which are plain `Copy` values with no lifetime:
+///
+/// ```ignore
+/// let mut pool: CountDownPool<_, 4> = CountDownPool::new(systick);
+///
+/// let mut radio = Radio::new(spi, pool.allocate().unwrap());
+/// let mut display = Display::new(i2c, pool.allocate().unwrap());
+///
+/// loop {
+///     radio.poll(&mut pool);
+///     display.poll(&mut pool);
+/// }
+/// ```
+pub struct CountDownPool<CM: CountsMillis, const N: usize> {
+    counter: CM,
+    slots: [Slot; N],
+    /// Bumped each time a slot is released, to invalidate its old handles.
+    generations: [u32; N],
+}
+
+impl<CM: CountsMillis, const N: usize> CountDownPool<CM, N> {
+    /// Creates a pool of `N` countdowns, none allocated, timed by `counter`.
+    pub fn new(counter: CM) -> Self {
+        CountDownPool {
+            counter,
+            slots: [Slot::Free; N],
+            generations: [0; N],
+        }
+    }
+
+    /// Returns the counter that the pool times with.
+    pub fn counter(&self) -> &CM {
+        &self.counter
+    }
+
+    /// Gives back the counter.
+    pub fn free(self) -> CM {
+        self.counter
+    }
+
+    /// Allocates a countdown. It isn’t running until it’s started.
+    pub fn allocate(&mut self) -> Result<PoolTimer, CountDownPoolError> {
+        let i = self
+            .slots
+            .iter()
+            .position(|slot| matches!(slot, Slot::Free))
+            .ok_or(CountDownPoolError::Full)?;
+
+        self.slots[i] = Slot::Stopped;
+
+        Ok(PoolTimer {
+            index: i,
+            generation: self.generations[i],
+        })
+    }
+
+    /// Returns a countdown to the pool. Returns false if it was already
+    /// released.
+    pub fn release(&mut self, timer: PoolTimer) -> bool {
+        match self.slot_mut(timer) {
+            Some(slot @ (Slot::Stopped | Slot::Running(_))) => {
+                *slot = Slot::Free;
+                self.generations[timer.index] = self.generations[timer.index].wrapping_add(1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the slot for `timer`, or `None` if it’s been released since
+    /// `timer` was allocated.
+    fn slot(&self, timer: PoolTimer) -> Option<&Slot> {
+        match self.generations.get(timer.index) {
+            Some(&generation) if generation == timer.generation => self.slots.get(timer.index),
+            _ => None,
+        }
+    }
+
+    fn slot_mut(&mut self, timer: PoolTimer) -> Option<&mut Slot> {
+        match self.generations.get(timer.index) {
+            Some(&generation) if generation == timer.generation => self.slots.get_mut(timer.index),
+            _ => None,
+        }
+    }
+
+    /// Starts (or restarts) a countdown for `ms` milliseconds. Returns false if
+    /// it has been released.
+    pub fn start_ms(&mut self, timer: PoolTimer, ms: u32) -> bool {
+        let target = self.counter.count() + Wrapping(ms);
+
+        match self.slot_mut(timer) {
+            Some(slot @ (Slot::Stopped | Slot::Running(_))) => {
+                *slot = Slot::Running(target);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the countdown has been started and its time has passed.
+    ///
+    /// It stays expired until it’s started again or cancelled.
+    pub fn expired(&self, timer: PoolTimer) -> bool {
+        match self.slot(timer) {
+            Some(Slot::Running(target)) => has_passed(self.counter.count(), *target),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the countdown has been started and hasn’t been
+    /// cancelled, whether or not it has expired.
+    pub fn is_running(&self, timer: PoolTimer) -> bool {
+        matches!(self.slot(timer), Some(Slot::Running(_)))
+    }
+
+    /// Stops a countdown without releasing it. Returns false if it wasn’t
+    /// running.
+    pub fn cancel(&mut self, timer: PoolTimer) -> bool {
+        match self.slot_mut(timer) {
+            Some(slot @ Slot::Running(_)) => {
+                *slot = Slot::Stopped;
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use core::num::Wrapping;
+
+use crate::CountsMicros;
+
+/// Summary of the durations recorded by a [`Profiler`](struct.Profiler.html),
+/// in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProfileStats {
+    /// Shortest duration.
+    pub min_us: u32,
+    /// Longest duration.
+    pub max_us: u32,
+    /// Average duration, rounded down.
+    pub mean_us: u32,
+    /// Most recent duration.
+    pub last_us: u32,
+    /// Number of durations recorded.
+    pub samples: u32,
+}
+
+/// Keeps statistics on how long a piece of code takes to run
+///
+/// Times are taken from [`CountsMicros`](trait.CountsMicros.html), so they
+/// include how far SysTick is through the current millisecond.
+///
+/// Either wrap the code in [`measure`](#method.measure), or call
+/// [`enter`](#method.enter) and [`exit`](#method.exit) around it when a
+/// closure is awkward, such as at the start and end of an interrupt handler.
+///
+/// ```ignore
+/// let mut loop_profile = Profiler::new(&systick);
+///
+/// loop {
+///     loop_profile.measure(|| do_work());
+///
+///     if let Some(stats) = loop_profile.stats() {
+///         log!("loop max {}us", stats.max_us);
+///     }
+/// }
+/// ```
+///
+/// Individual durations can be up to about 71.6 minutes before they wrap
+/// around.
+pub struct Profiler<'a, CM: CountsMicros> {
+    counter: &'a CM,
+    entered_micros: Option<Wrapping<u32>>,
+    min_us: u32,
+    max_us: u32,
+    total_us: u64,
+    last_us: u32,
+    samples: u32,
+}
+
+impl<'a, CM: CountsMicros> Profiler<'a, CM> {
+    /// Creates a profiler with no durations recorded.
+    pub fn new(counter: &'a CM) -> Self {
+        Profiler {
+            counter,
+            entered_micros: None,
+            min_us: u32::MAX,
+            max_us: 0,
+            total_us: 0,
+            last_us: 0,
+            samples: 0,
+        }
+    }
+
+    /// Runs `f`, recording how long it took.
+    pub fn measure<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+        self.enter();
+        let result = f();
+        self.exit();
+
+        result
+    }
+
+    /// Starts timing.
+    pub fn enter(&mut self) {
+        self.entered_micros = Some(self.counter.count_micros());
+    }
+
+    /// Stops timing and records the duration since [`enter`](#method.enter).
+    /// Returns it, or `None` if `enter` wasn’t called first.
+    pub fn exit(&mut self) -> Option<u32> {
+        let entered = self.entered_micros.take()?;
+        let duration = (self.counter.count_micros() - entered).0;
+
+        self.record(duration);
+        Some(duration)
+    }
+
+    /// Adds a duration measured some other way.
+    pub fn record(&mut self, duration_us: u32) {
+        self.min_us = self.min_us.min(duration_us);
+        self.max_us = self.max_us.max(duration_us);
+        self.total_us += u64::from(duration_us);
+        self.last_us = duration_us;
+        self.samples = self.samples.saturating_add(1);
+    }
+
+    /// Returns the statistics so far, or `None` if nothing has been recorded.
+    pub fn stats(&self) -> Option<ProfileStats> {
+        if self.samples == 0 {
+            return None;
+        }
+
+        Some(ProfileStats {
+            min_us: self.min_us,
+            max_us: self.max_us,
+            mean_us: (self.total_us / u64::from(self.samples)) as u32,
+            last_us: self.last_us,
+            samples: self.samples,
+        })
+    }
+
+    /// Forgets all recorded durations.
+    pub fn reset(&mut self) {
+        *self = Profiler::new(self.counter);
+    }
+}
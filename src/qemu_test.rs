@@ -0,0 +1,94 @@
+//! Helpers for timing tests run under QEMU with semihosting, enabled by the
+//! `qemu-test` feature.
+//!
+//! Like the crate’s `qemu` example, but for tests that should pass or fail on
+//! their own: time something, compare it with what you expected using
+//! [`expected_vs_actual`](fn.expected_vs_actual.html), print the results to
+//! the host, and [`exit`](fn.exit.html) with a status that CI can check.
+//!
+//! ```ignore
+//! let mut hstdout = hio::hstdout().unwrap();
+//! let mut stopwatch = Stopwatch::new(&systick);
+//! stopwatch.start();
+//! (&systick).delay_ms(500u32);
+//!
+//! let check = qemu_test::expected_vs_actual(500, stopwatch.elapsed_ms().unwrap(), 5);
+//! check.report(&mut hstdout, "delay_ms(500)").unwrap();
+//! qemu_test::report_elapsed(&mut hstdout, &systick).unwrap();
+//!
+//! qemu_test::exit(&[check]);
+//! ```
+
+use core::fmt::{self, Write};
+
+use cortex_m_semihosting::debug;
+use cortex_m_semihosting::hio::HStdout;
+
+use crate::CountsMillis64;
+
+/// Result of comparing a measured time against the expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimingCheck {
+    /// How long it should have taken.
+    pub expected_ms: u32,
+    /// How long it did take.
+    pub actual_ms: u32,
+    /// How far off `actual_ms` can be, either way, and still pass.
+    pub tolerance_ms: u32,
+}
+
+impl TimingCheck {
+    /// Returns how much longer (or, if negative, shorter) it took than
+    /// expected.
+    pub fn error_ms(&self) -> i64 {
+        i64::from(self.actual_ms) - i64::from(self.expected_ms)
+    }
+
+    /// Returns true if the actual time was within the tolerance.
+    pub fn passed(&self) -> bool {
+        self.error_ms().unsigned_abs() <= u64::from(self.tolerance_ms)
+    }
+
+    /// Prints a line like `label: expected 500ms, got 502ms (+2ms) ok` to the
+    /// host.
+    pub fn report(&self, hstdout: &mut HStdout, label: &str) -> fmt::Result {
+        writeln!(
+            hstdout,
+            "{}: expected {}ms, got {}ms ({:+}ms) {}",
+            label,
+            self.expected_ms,
+            self.actual_ms,
+            self.error_ms(),
+            if self.passed() { "ok" } else { "FAILED" }
+        )
+    }
+}
+
+/// Compares a measured time against the expected one, allowing it to be off by
+/// up to `tolerance_ms` either way.
+///
+/// Assert on the result’s [`passed`](struct.TimingCheck.html#method.passed)
+/// directly, or collect several and hand them to [`exit`](fn.exit.html).
+pub fn expected_vs_actual(expected_ms: u32, actual_ms: u32, tolerance_ms: u32) -> TimingCheck {
+    TimingCheck {
+        expected_ms,
+        actual_ms,
+        tolerance_ms,
+    }
+}
+
+/// Prints the time `counter` has been counting to the host, like
+/// `elapsed: 3012ms`.
+pub fn report_elapsed<CM: CountsMillis64>(hstdout: &mut HStdout, counter: &CM) -> fmt::Result {
+    writeln!(hstdout, "elapsed: {}ms", counter.count64())
+}
+
+/// Ends the QEMU session, successfully if every check passed.
+pub fn exit(checks: &[TimingCheck]) {
+    if checks.iter().all(TimingCheck::passed) {
+        debug::exit(debug::EXIT_SUCCESS);
+    } else {
+        debug::exit(debug::EXIT_FAILURE);
+    }
+}
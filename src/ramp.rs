@@ -0,0 +1,85 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Value that moves in a straight line from one number to another over a
+/// number of milliseconds
+///
+/// For LED fades, motor soft-starts and setpoint ramps: read
+/// [`value_now`](#method.value_now) whenever you update the output, and it
+/// says where along the way it should be. There’s nothing to poll.
+///
+/// ```ignore
+/// let mut fade = Ramp::new(&systick, 0, 255, 2_000);
+///
+/// while !fade.is_done() {
+///     pwm.set_duty(fade.value_now() as u16);
+/// }
+/// ```
+///
+/// Ramps must be less than about 24.8 days long.
+pub struct Ramp<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    from: i32,
+    to: i32,
+    duration_ms: u32,
+    started: Wrapping<u32>,
+}
+
+impl<'a, CM: CountsMillis> Ramp<'a, CM> {
+    /// Starts a ramp from `from` to `to` that takes `duration_ms`, timed with
+    /// `counter`.
+    pub fn new(counter: &'a CM, from: i32, to: i32, duration_ms: u32) -> Self {
+        Ramp {
+            counter,
+            from,
+            to,
+            duration_ms,
+            started: counter.count(),
+        }
+    }
+
+    /// Returns where the ramp is now: `from` when it starts, `to` once it’s
+    /// done, and in proportion to the time that’s passed in between.
+    pub fn value_now(&self) -> i32 {
+        let elapsed = self.elapsed_ms();
+
+        if elapsed >= self.duration_ms {
+            return self.to;
+        }
+
+        let span = i64::from(self.to) - i64::from(self.from);
+        let offset = span * i64::from(elapsed) / i64::from(self.duration_ms);
+
+        // Between `from` and `to`, so it fits back in an i32.
+        (i64::from(self.from) + offset) as i32
+    }
+
+    /// Returns true once the value has reached `to`.
+    pub fn is_done(&self) -> bool {
+        self.elapsed_ms() >= self.duration_ms
+    }
+
+    /// Returns the value the ramp ends at.
+    pub fn target(&self) -> i32 {
+        self.to
+    }
+
+    /// Starts the same ramp over from `from`.
+    pub fn restart(&mut self) {
+        self.started = self.counter.count();
+    }
+
+    /// Starts a new ramp to `to` from wherever this one is now, so that the
+    /// value doesn’t jump when the setpoint changes partway through.
+    pub fn retarget(&mut self, to: i32, duration_ms: u32) {
+        self.from = self.value_now();
+        self.to = to;
+        self.duration_ms = duration_ms;
+        self.started = self.counter.count();
+    }
+
+    fn elapsed_ms(&self) -> u32 {
+        (self.counter.count() - self.started).0
+    }
+}
@@ -0,0 +1,87 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Measures how often something happens
+///
+/// Call [`tick`](#method.tick) each time the event happens. Events are
+/// counted in back-to-back windows of `window_ms`, and the rate reported is
+/// from the last complete window.
+///
+/// ```ignore
+/// let mut rate = RateMeter::new(&systick, 1_000);
+///
+/// loop {
+///     if adc.sample_ready() {
+///         rate.tick();
+///     }
+///
+///     display.show(rate.per_second());
+/// }
+/// ```
+pub struct RateMeter<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    window_ms: u32,
+    window_start: Wrapping<u32>,
+    current: u32,
+    last: u32,
+}
+
+impl<'a, CM: CountsMillis> RateMeter<'a, CM> {
+    /// Creates a `RateMeter` that counts events over windows of `window_ms`.
+    /// A `window_ms` of 0 is treated as 1.
+    pub fn new(counter: &'a CM, window_ms: u32) -> Self {
+        RateMeter {
+            counter,
+            window_ms: window_ms.max(1),
+            window_start: counter.count(),
+            current: 0,
+            last: 0,
+        }
+    }
+
+    /// Records one event.
+    pub fn tick(&mut self) {
+        self.tick_n(1);
+    }
+
+    /// Records `n` events at once.
+    pub fn tick_n(&mut self, n: u32) {
+        self.roll();
+        self.current = self.current.saturating_add(n);
+    }
+
+    /// Returns how many events happened in the last complete window.
+    pub fn per_window(&mut self) -> u32 {
+        self.roll();
+        self.last
+    }
+
+    /// Returns the last complete window’s rate in events per second.
+    pub fn per_second(&mut self) -> u32 {
+        let per_window = u64::from(self.per_window());
+        let per_second = per_window * 1000 / u64::from(self.window_ms);
+
+        per_second.min(u64::from(u32::MAX)) as u32
+    }
+
+    /// Forgets all events and starts a new window from now.
+    pub fn reset(&mut self) {
+        self.window_start = self.counter.count();
+        self.current = 0;
+        self.last = 0;
+    }
+
+    /// Moves on to a new window if the current one is over.
+    fn roll(&mut self) {
+        let elapsed = (self.counter.count() - self.window_start).0;
+        let windows = elapsed / self.window_ms;
+
+        if windows > 0 {
+            // If more than one window passed, the most recent was empty.
+            self.last = if windows == 1 { self.current } else { 0 };
+            self.current = 0;
+            self.window_start += Wrapping(windows * self.window_ms);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use core::convert::TryFrom;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_m::peripheral::SYST;
+
+use crate::{critical, CalibrationError, SysTickCalibration};
+
+/// A SysTick period that may not be a whole number of clock ticks.
+#[derive(Clone, Copy)]
+pub(crate) struct TickReload {
+    /// Reload value for the whole ticks in the period.
+    pub reload: u32,
+    /// Fraction of a clock tick (out of 2³²) beyond `reload + 1` ticks.
+    pub fraction: u32,
+}
+
+impl TickReload {
+    /// Makes a period from a number of ticks in 32.32 fixed point, checking
+    /// that SysTick can count it.
+    pub fn from_fixed(ticks: u64) -> Result<Self, CalibrationError> {
+        let whole = (ticks >> 32) as u32;
+        let fraction = ticks as u32;
+
+        // A reload value of 0 stops SysTick.
+        if whole < 2 {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        // A fractional period sometimes needs one more tick.
+        if whole - 1 + u32::from(fraction != 0) > SysTickCalibration::MAX_RELOAD {
+            return Err(CalibrationError::ReloadTooLarge);
+        }
+
+        Ok(TickReload {
+            reload: whole - 1,
+            fraction,
+        })
+    }
+
+    /// The number of ticks in the period, in 32.32 fixed point.
+    pub fn to_fixed(self) -> u64 {
+        (u64::from(self.reload) + 1) << 32 | u64::from(self.fraction)
+    }
+
+    /// Lengthens (or, if negative, shortens) the period by `ppm` parts per
+    /// million.
+    pub fn trimmed(self, ppm: i32) -> Result<Self, CalibrationError> {
+        if ppm == 0 {
+            return Ok(self);
+        }
+
+        let scale = 1_000_000 + i64::from(ppm);
+
+        if scale <= 0 {
+            return Err(CalibrationError::ClockTooSlow);
+        }
+
+        let ticks = u128::from(self.to_fixed()) * scale as u128 / 1_000_000;
+
+        match u64::try_from(ticks) {
+            Ok(ticks) => Self::from_fixed(ticks),
+            Err(_) => Err(CalibrationError::ReloadTooLarge),
+        }
+    }
+}
+
+/// Keeps SysTick’s average period accurate when it isn’t a whole number of
+/// clock ticks.
+///
+/// Each time SysTick wraps we add the period’s fraction to an accumulator.
+/// When that overflows, the next period is made one tick longer by bumping the
+/// reload value for it. (SysTick only loads the reload value when it wraps, so
+/// changing it doesn’t affect the period that’s already underway.) Over time
+/// the periods average out to the exact length.
+///
+/// Since the reload value register holds the *next* period’s reload by the
+/// time anything reads it, this also keeps track of the reload value of the
+/// period that’s underway, for working out how far into it SysTick is.
+pub(crate) struct ReloadDither {
+    reload: AtomicU32,
+    fraction: AtomicU32,
+    accumulated: AtomicU32,
+    /// Reload value last written to SysTick, for the period after this one.
+    next: AtomicU32,
+    /// Reload value that the period underway started from.
+    running: AtomicU32,
+}
+
+impl ReloadDither {
+    pub const fn new() -> Self {
+        ReloadDither {
+            reload: AtomicU32::new(0),
+            fraction: AtomicU32::new(0),
+            accumulated: AtomicU32::new(0),
+            next: AtomicU32::new(0),
+            running: AtomicU32::new(0),
+        }
+    }
+
+    /// Switches to a new period, starting after SysTick next wraps. The
+    /// accumulated fraction is kept, since it’s still owed to the count.
+    pub fn set(&self, tick: TickReload) {
+        critical::free(|_| {
+            self.reload.store(tick.reload, Ordering::Relaxed);
+            self.fraction.store(tick.fraction, Ordering::Relaxed);
+            self.next.store(tick.reload, Ordering::Relaxed);
+
+            // We own SYST, so we’re the only ones writing the reload value.
+            unsafe { (*SYST::PTR).rvr.write(tick.reload) };
+        });
+    }
+
+    /// Called after clearing SysTick’s current value, which starts a new
+    /// period from the reload value right away rather than at the next wrap.
+    pub fn restart(&self) {
+        self.running
+            .store(self.next.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Returns the reload value of the period that’s underway, as of the last
+    /// `on_wrap` or `restart`.
+    pub fn running_reload(&self) -> u32 {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Returns the current period.
+    pub fn tick(&self) -> TickReload {
+        TickReload {
+            reload: self.reload.load(Ordering::Relaxed),
+            fraction: self.fraction.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Called after each wrap to choose the length of the period after the one
+    /// that just started.
+    pub fn on_wrap(&self) {
+        self.restart();
+
+        let fraction = self.fraction.load(Ordering::Relaxed);
+
+        // Exact periods never need adjusting, so skip the critical section.
+        if fraction == 0 {
+            return;
+        }
+
+        critical::free(|_| {
+            let (accumulated, carry) = self
+                .accumulated
+                .load(Ordering::Relaxed)
+                .overflowing_add(fraction);
+
+            self.accumulated.store(accumulated, Ordering::Relaxed);
+
+            let reload = self.reload.load(Ordering::Relaxed) + u32::from(carry);
+            self.next.store(reload, Ordering::Relaxed);
+
+            // We own SYST, so we’re the only ones writing the reload value.
+            unsafe { (*SYST::PTR).rvr.write(reload) };
+        });
+    }
+}
+
+/// Returns how many ticks SysTick has counted down in the period underway,
+/// which started from `reload`.
+///
+/// Saturates rather than underflowing, since a polled counter only finds out
+/// that SysTick has wrapped into a longer, dithered period when it next polls,
+/// and until then `reload` can be one less than the current value.
+pub(crate) fn ticks_into_period(reload: u32) -> u32 {
+    reload.saturating_sub(SYST::get_current())
+}
@@ -0,0 +1,42 @@
+//! The SysTick exception handler for `InterruptSysTick`, enabled by the `rt`
+//! feature.
+
+use core::cell::Cell;
+
+use cortex_m_rt::exception;
+
+use crate::critical::{self, Mutex};
+use crate::InterruptSysTick;
+
+/// What `set_tick_hook` set, if anything.
+type Hook = Option<fn()>;
+
+static HOOK: Mutex<Cell<Hook>> = Mutex::new(Cell::new(None));
+
+/// Sets a function for the SysTick exception handler to call after each tick,
+/// or removes it with `None`.
+///
+/// Only available with the `rt` feature, which defines the handler so that
+/// your program doesn’t have to. The hook runs in the handler, after the count
+/// has been advanced and any alarm has gone off, so it should be quick.
+///
+/// ```ignore
+/// fn every_tick() {
+///     debounce_buttons();
+/// }
+///
+/// let systick = InterruptSysTick::new(syst, &calibration);
+/// set_tick_hook(Some(every_tick));
+/// ```
+pub fn set_tick_hook(hook: Option<fn()>) {
+    critical::free(|cs| HOOK.borrow(cs).set(hook));
+}
+
+#[exception]
+fn SysTick() {
+    InterruptSysTick::on_interrupt();
+
+    if let Some(hook) = critical::free(|cs| HOOK.borrow(cs).get()) {
+        hook();
+    }
+}
@@ -0,0 +1,78 @@
+//! RTIC 2 monotonic, enabled by the `rtic` feature.
+//!
+//! ## Usage
+//!
+//! Create an [`InterruptSysTick`](../struct.InterruptSysTick.html) in your
+//! `init` task and call its `on_interrupt` from the SysTick exception handler.
+//! After that, [`SysTickMono`](struct.SysTickMono.html) works like any other
+//! RTIC monotonic:
+//!
+//! ```ignore
+//! use rtic_time::Monotonic;
+//! use fugit::ExtU64;
+//!
+//! SysTickMono::delay(500.millis()).await;
+//! ```
+
+use cortex_m::peripheral::SCB;
+
+use rtic_time::monotonic::TimerQueueBasedMonotonic;
+use rtic_time::timer_queue::{TimerQueue, TimerQueueBackend};
+
+static TIMER_QUEUE: TimerQueue<SysTickBackend> = TimerQueue::new();
+
+/// Timer queue backend that reads the count kept by `InterruptSysTick`.
+///
+/// You shouldn’t need to use this directly. It’s public because it’s part of
+/// [`SysTickMono`](struct.SysTickMono.html)’s `TimerQueueBasedMonotonic`
+/// implementation.
+pub struct SysTickBackend;
+
+impl TimerQueueBackend for SysTickBackend {
+    type Ticks = u64;
+
+    fn now() -> u64 {
+        crate::interrupt::count64()
+    }
+
+    /// SysTick can’t be set to fire at a particular time, but since the
+    /// exception fires every millisecond anyway it will check the queue soon
+    /// enough.
+    fn set_compare(_instant: u64) {}
+
+    fn clear_compare_flag() {}
+
+    fn pend_interrupt() {
+        SCB::set_pendst();
+    }
+
+    fn timer_queue() -> &'static TimerQueue<Self> {
+        &TIMER_QUEUE
+    }
+}
+
+/// RTIC monotonic with millisecond ticks, driven by
+/// [`InterruptSysTick`](../struct.InterruptSysTick.html).
+///
+/// Implements `rtic_time::Monotonic` through `TimerQueueBasedMonotonic`, using
+/// 64-bit fugit instants and durations so that it never wraps.
+pub struct SysTickMono;
+
+impl TimerQueueBasedMonotonic for SysTickMono {
+    type Backend = SysTickBackend;
+    type Instant = fugit::TimerInstantU64<1_000>;
+    type Duration = fugit::TimerDurationU64<1_000>;
+}
+
+/// Marks the timer queue as ready. Called when `InterruptSysTick` is created.
+pub(crate) fn initialize() {
+    TIMER_QUEUE.initialize(SysTickBackend);
+}
+
+/// Wakes any RTIC tasks whose delays have passed. Called from
+/// `InterruptSysTick::on_interrupt`.
+pub(crate) fn on_interrupt() {
+    // Safety: we’re only ever called from the SysTick exception handler, by
+    // way of InterruptSysTick::on_interrupt.
+    unsafe { TIMER_QUEUE.on_monotonic_interrupt() };
+}
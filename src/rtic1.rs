@@ -0,0 +1,90 @@
+//! RTIC 1 monotonic, enabled by the `rtic1` feature.
+//!
+//! ## Usage
+//!
+//! Make a [`SysTickMonotonic`](struct.SysTickMonotonic.html) in `init` and
+//! return it as the monotonic bound to SysTick. RTIC defines the SysTick
+//! handler itself and calls the monotonic’s `on_interrupt`, so unlike with
+//! `InterruptSysTick` you don’t write one:
+//!
+//! ```ignore
+//! #[monotonic(binds = SysTick, default = true)]
+//! type Mono = SysTickMonotonic;
+//!
+//! #[init]
+//! fn init(cx: init::Context) -> (Shared, Local, init::Monotonics) {
+//!     let mono = SysTickMonotonic::new(cx.core.SYST, &calibration);
+//!     (Shared {}, Local {}, init::Monotonics(mono))
+//! }
+//! ```
+//!
+//! For RTIC 2, use the `rtic` feature’s
+//! [`SysTickMono`](../rtic/struct.SysTickMono.html) instead.
+
+use cortex_m::peripheral::SYST;
+
+use rtic_monotonic::Monotonic;
+
+use crate::{interrupt, InterruptSysTick, SysTickCalibration, SysTickPeripheral};
+
+/// `rtic_monotonic::Monotonic` with millisecond ticks, for RTIC 1
+///
+/// Counts with an [`InterruptSysTick`](../struct.InterruptSysTick.html), and
+/// uses 64-bit fugit instants and durations so that it never wraps. Other code
+/// can read the same count through a `CountsMillis` on the `InterruptSysTick`
+/// from [`systick`](#method.systick).
+pub struct SysTickMonotonic {
+    systick: InterruptSysTick,
+}
+
+impl SysTickMonotonic {
+    /// Configures SysTick to fire its exception every millisecond.
+    pub fn new<P: SysTickPeripheral>(syst: P, calibration: &SysTickCalibration) -> Self {
+        SysTickMonotonic {
+            systick: InterruptSysTick::new(syst, calibration),
+        }
+    }
+
+    /// Returns the underlying `InterruptSysTick`, for making countdowns or
+    /// delays.
+    pub fn systick(&self) -> &InterruptSysTick {
+        &self.systick
+    }
+
+    /// Turns this value back into the underlying SysTick.
+    pub fn free(self) -> SYST {
+        self.systick.free()
+    }
+}
+
+impl Monotonic for SysTickMonotonic {
+    // The exception has to keep firing for the count to advance, even when
+    // RTIC has nothing scheduled.
+    const DISABLE_INTERRUPT_ON_EMPTY_QUEUE: bool = false;
+
+    type Instant = fugit::TimerInstantU64<1_000>;
+    type Duration = fugit::TimerDurationU64<1_000>;
+
+    fn now(&mut self) -> Self::Instant {
+        Self::Instant::from_ticks(interrupt::count64())
+    }
+
+    /// SysTick can’t be set to fire at a particular time, but since the
+    /// exception fires every millisecond anyway, RTIC checks its queue soon
+    /// enough.
+    fn set_compare(&mut self, _instant: Self::Instant) {}
+
+    fn clear_compare_flag(&mut self) {}
+
+    fn zero() -> Self::Instant {
+        Self::Instant::from_ticks(0)
+    }
+
+    unsafe fn reset(&mut self) {
+        interrupt::reset_count();
+    }
+
+    fn on_interrupt(&mut self) {
+        InterruptSysTick::on_interrupt();
+    }
+}
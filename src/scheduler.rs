@@ -0,0 +1,140 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis};
+
+enum Job<'a> {
+    Fn(fn()),
+    Closure(&'a mut dyn FnMut()),
+}
+
+struct Task<'a> {
+    job: Job<'a>,
+    period_ms: u32,
+    next_run: Wrapping<u32>,
+}
+
+/// Identifies a task added to a [`Scheduler`](struct.Scheduler.html), so that
+/// it can be removed later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TaskId(usize);
+
+/// Errors from [`Scheduler`](struct.Scheduler.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SchedulerError {
+    /// The scheduler already has `N` tasks.
+    Full,
+    /// The period was 0, which would have the task run on every call to
+    /// `run_pending`.
+    ZeroPeriod,
+}
+
+/// Cooperative scheduler for running up to `N` tasks at fixed periods
+///
+/// This is the “blink without delay” pattern from Arduino, packaged up: add
+/// each task with how often it should run, and then call
+/// [`run_pending`](#method.run_pending) from your main loop. Tasks run to
+/// completion one at a time, so keep them short.
+///
+/// ```ignore
+/// let mut read_sensor = || sensor.sample();
+///
+/// let mut scheduler: Scheduler<_, 4> = Scheduler::new(&systick);
+/// scheduler.add_fn(500, toggle_led).unwrap();
+/// scheduler.add(100, &mut read_sensor).unwrap();
+///
+/// loop {
+///     scheduler.run_pending();
+/// }
+/// ```
+///
+/// Each task first runs one period after it’s added. Later runs are timed from
+/// when the previous run was due, so they don’t drift. If the loop falls more
+/// than a whole period behind, the task runs once and then starts over from
+/// the current time, rather than running repeatedly to catch up.
+pub struct Scheduler<'a, CM: CountsMillis, const N: usize> {
+    counter: &'a CM,
+    tasks: [Option<Task<'a>>; N],
+}
+
+impl<'a, CM: CountsMillis, const N: usize> Scheduler<'a, CM, N> {
+    /// Creates a scheduler with no tasks that times with `counter`.
+    pub fn new(counter: &'a CM) -> Self {
+        Scheduler {
+            counter,
+            tasks: [const { None }; N],
+        }
+    }
+
+    /// Adds a closure to run every `period_ms` milliseconds.
+    ///
+    /// Fails if the scheduler is full, or if `period_ms` is 0.
+    pub fn add(
+        &mut self,
+        period_ms: u32,
+        task: &'a mut dyn FnMut(),
+    ) -> Result<TaskId, SchedulerError> {
+        self.insert(period_ms, Job::Closure(task))
+    }
+
+    /// Adds a function to run every `period_ms` milliseconds.
+    ///
+    /// Fails if the scheduler is full, or if `period_ms` is 0.
+    pub fn add_fn(&mut self, period_ms: u32, task: fn()) -> Result<TaskId, SchedulerError> {
+        self.insert(period_ms, Job::Fn(task))
+    }
+
+    fn insert(&mut self, period_ms: u32, job: Job<'a>) -> Result<TaskId, SchedulerError> {
+        if period_ms == 0 {
+            return Err(SchedulerError::ZeroPeriod);
+        }
+
+        let i = self
+            .tasks
+            .iter()
+            .position(Option::is_none)
+            .ok_or(SchedulerError::Full)?;
+
+        self.tasks[i] = Some(Task {
+            job,
+            period_ms,
+            next_run: self.counter.count() + Wrapping(period_ms),
+        });
+
+        Ok(TaskId(i))
+    }
+
+    /// Removes a task. Returns false if it was already removed.
+    pub fn remove(&mut self, id: TaskId) -> bool {
+        self.tasks.get_mut(id.0).and_then(Option::take).is_some()
+    }
+
+    /// Runs every task that is due, and returns how many ran.
+    pub fn run_pending(&mut self) -> usize {
+        let mut ran = 0;
+
+        for task in self.tasks.iter_mut().flatten() {
+            let now = self.counter.count();
+
+            if !has_passed(now, task.next_run) {
+                continue;
+            }
+
+            task.next_run += Wrapping(task.period_ms);
+
+            if has_passed(now, task.next_run) {
+                task.next_run = now + Wrapping(task.period_ms);
+            }
+
+            match &mut task.job {
+                Job::Fn(f) => f(),
+                Job::Closure(f) => f(),
+            }
+
+            ran += 1;
+        }
+
+        ran
+    }
+}
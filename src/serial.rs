@@ -0,0 +1,133 @@
+use embedded_hal::serial::Read;
+
+use crate::{CountsMillis, MillisCountDown, TimeoutError};
+
+/// Serial port reader whose reads give up after a timeout
+///
+/// Wraps an embedded-hal 0.2 `serial::Read<u8>`, such as a HAL’s UART
+/// receiver. Each byte has to arrive within `byte_timeout_ms` of starting to
+/// wait for it, which catches a sender that stops partway through a frame.
+/// Reads of more than one byte also take a limit for the whole read, which
+/// catches a sender that trickles bytes in too slowly.
+///
+/// ```ignore
+/// let mut rx = TimedSerial::new(rx, &systick, 20);
+///
+/// let mut header = [0; 4];
+/// rx.read_exact(&mut header, 100)?;
+///
+/// let mut line = [0; 64];
+/// let len = rx.read_until(&mut line, b'\n', 500)?;
+/// ```
+///
+/// A `TimeoutError::Other` holds the serial port’s own error, such as an
+/// overrun or framing error.
+pub struct TimedSerial<'a, S, CM: CountsMillis> {
+    serial: S,
+    counter: &'a CM,
+    byte_timeout_ms: u32,
+}
+
+impl<'a, S: Read<u8>, CM: CountsMillis> TimedSerial<'a, S, CM> {
+    /// Wraps `serial`, timing with `counter`. Each byte has to arrive within
+    /// `byte_timeout_ms`.
+    pub fn new(serial: S, counter: &'a CM, byte_timeout_ms: u32) -> Self {
+        TimedSerial {
+            serial,
+            counter,
+            byte_timeout_ms,
+        }
+    }
+
+    /// Changes how long each byte can take to arrive.
+    pub fn set_byte_timeout_ms(&mut self, byte_timeout_ms: u32) {
+        self.byte_timeout_ms = byte_timeout_ms;
+    }
+
+    /// Returns the wrapped serial port, for reading from it directly.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.serial
+    }
+
+    /// Turns this value back into the wrapped serial port.
+    pub fn free(self) -> S {
+        self.serial
+    }
+
+    /// Reads one byte, waiting at most the per-byte timeout.
+    pub fn read_byte(&mut self) -> Result<u8, TimeoutError<S::Error>> {
+        self.read_within(None)
+    }
+
+    /// Fills `buf`, waiting at most the per-byte timeout for each byte and
+    /// `total_ms` for all of them.
+    ///
+    /// If it times out, the bytes read so far are left at the start of `buf`,
+    /// but there’s no telling how many there were. Use
+    /// [`read_until`](#method.read_until) for frames that can be cut short.
+    pub fn read_exact(
+        &mut self,
+        buf: &mut [u8],
+        total_ms: u32,
+    ) -> Result<(), TimeoutError<S::Error>> {
+        let mut total = MillisCountDown::new(self.counter);
+        total.start_ms(total_ms);
+
+        for byte in buf.iter_mut() {
+            *byte = self.read_within(Some(&mut total))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads into `buf` until `delimiter` arrives or `buf` is full, waiting at
+    /// most the per-byte timeout for each byte and `total_ms` for all of
+    /// them.
+    ///
+    /// Returns how many bytes were read, including the delimiter if it arrived.
+    pub fn read_until(
+        &mut self,
+        buf: &mut [u8],
+        delimiter: u8,
+        total_ms: u32,
+    ) -> Result<usize, TimeoutError<S::Error>> {
+        let mut total = MillisCountDown::new(self.counter);
+        total.start_ms(total_ms);
+
+        for (i, slot) in buf.iter_mut().enumerate() {
+            let byte = self.read_within(Some(&mut total))?;
+            *slot = byte;
+
+            if byte == delimiter {
+                return Ok(i + 1);
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn read_within(
+        &mut self,
+        mut total: Option<&mut MillisCountDown<'a, CM>>,
+    ) -> Result<u8, TimeoutError<S::Error>> {
+        let mut per_byte = MillisCountDown::new(self.counter);
+        per_byte.start_ms(self.byte_timeout_ms);
+
+        loop {
+            match self.serial.read() {
+                Ok(byte) => return Ok(byte),
+                Err(nb::Error::Other(err)) => return Err(TimeoutError::Other(err)),
+                Err(nb::Error::WouldBlock) => {
+                    let total_passed = match total.as_mut() {
+                        Some(total) => total.try_wait_ms().is_ok(),
+                        None => false,
+                    };
+
+                    if total_passed || per_byte.try_wait_ms().is_ok() {
+                        return Err(TimeoutError::TimedOut);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use core::num::Wrapping;
+
+use crate::{CountsMicros, CountsMillis, CountsMillis64, PollingSysTick};
+
+/// `PollingSysTick` that hands out any number of cheap handles to itself
+///
+/// Most HAL drivers take their delay provider by value, which would mean
+/// giving each one a SysTick of its own. A `SharedSysTick` keeps the one
+/// SysTick, and its [`handle`](#method.handle)s are `Copy` delay providers
+/// and counters that all read from it.
+///
+/// ```ignore
+/// let systick = SharedSysTick::new(PollingSysTick::new(syst, &calibration));
+///
+/// let display = Display::new(spi, systick.handle());
+/// let sensor = Sensor::new(i2c, systick.handle());
+/// ```
+pub struct SharedSysTick {
+    systick: PollingSysTick,
+}
+
+impl SharedSysTick {
+    /// Wraps `systick` so that it can be shared.
+    pub fn new(systick: PollingSysTick) -> Self {
+        SharedSysTick { systick }
+    }
+
+    /// Returns a handle to the shared SysTick.
+    pub fn handle(&self) -> SysTickHandle<'_> {
+        SysTickHandle::from(&self.systick)
+    }
+
+    /// Returns the `PollingSysTick`, once the handles are all gone.
+    pub fn free(self) -> PollingSysTick {
+        self.systick
+    }
+}
+
+/// `Copy`-able handle to a shared [`PollingSysTick`](struct.PollingSysTick.html)
+///
+/// Counts and delays just as the `PollingSysTick` does. Get one from a
+/// [`SharedSysTick`](struct.SharedSysTick.html), or from any
+/// `&PollingSysTick` with `From`, such as one kept in a
+/// [`CounterCell`](struct.CounterCell.html) to get handles with a `'static`
+/// lifetime.
+#[derive(Clone, Copy)]
+pub struct SysTickHandle<'a> {
+    pub(crate) systick: &'a PollingSysTick,
+}
+
+impl<'a> From<&'a PollingSysTick> for SysTickHandle<'a> {
+    fn from(systick: &'a PollingSysTick) -> Self {
+        SysTickHandle { systick }
+    }
+}
+
+impl<'a> CountsMillis for SysTickHandle<'a> {
+    fn count(&self) -> Wrapping<u32> {
+        self.systick.count()
+    }
+
+    fn resolution_ms(&self) -> u32 {
+        self.systick.resolution_ms()
+    }
+}
+
+impl<'a> CountsMillis64 for SysTickHandle<'a> {
+    fn count64(&self) -> u64 {
+        self.systick.count64()
+    }
+}
+
+impl<'a> CountsMicros for SysTickHandle<'a> {
+    fn count_micros(&self) -> Wrapping<u32> {
+        self.systick.count_micros()
+    }
+}
@@ -0,0 +1,257 @@
+use crate::MockMillis;
+
+struct Entry<Id> {
+    id: Id,
+    target: u64,
+    /// `None` for one-shot timers.
+    period_ms: Option<u32>,
+    /// Order the timer was (re-)armed in, to break ties between timers due at
+    /// the same time.
+    seq: u64,
+}
+
+/// A timer that went off during a [`SimClock`](struct.SimClock.html) run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Fired<Id> {
+    /// The timer’s ID.
+    pub id: Id,
+    /// The mock time when it went off.
+    pub at_ms: u64,
+}
+
+/// Simulated time for testing timer-heavy logic
+///
+/// Owns a [`MockMillis`](struct.MockMillis.html) and a set of one-shot and
+/// periodic timers, scheduled the same way as on a
+/// [`TimerQueue`](struct.TimerQueue.html). [`run_for`](#method.run_for) and
+/// [`run_until_idle`](#method.run_until_idle) step time from one deadline to
+/// the next, yielding each timer as it goes off, so tests can check exactly
+/// what happened in what order:
+///
+/// ```ignore
+/// let mut sim: SimClock<Event, 4> = SimClock::new();
+/// sim.schedule_periodic(Event::Poll, 10).unwrap();
+/// sim.schedule_once(Event::Timeout, 25).unwrap();
+///
+/// let fired: Vec<_> = sim.run_for(30).map(|fired| fired.id).collect();
+/// assert_eq!(fired, [Event::Poll, Event::Poll, Event::Timeout]);
+/// ```
+///
+/// Deadlines work like the rest of the crate: a timer scheduled for `ms` from
+/// now goes off once the count is past that, at `ms + 1`. Timers due at the
+/// same time go off in the order they were scheduled.
+pub struct SimClock<Id, const N: usize> {
+    clock: MockMillis,
+    entries: [Option<Entry<Id>>; N],
+    next_seq: u64,
+}
+
+impl<Id: Clone + PartialEq, const N: usize> SimClock<Id, N> {
+    /// Creates a simulation with no timers, starting at 0.
+    pub fn new() -> Self {
+        SimClock {
+            clock: MockMillis::new(),
+            entries: [const { None }; N],
+            next_seq: 0,
+        }
+    }
+
+    /// Returns the mock clock, for making countdowns or reading the time.
+    pub fn clock(&self) -> &MockMillis {
+        &self.clock
+    }
+
+    /// Returns the current mock time.
+    pub fn now_ms(&self) -> u64 {
+        self.clock.now_ms()
+    }
+
+    /// Schedules `id` to go off once, `ms` milliseconds from now.
+    ///
+    /// If `id` is already scheduled, it’s rescheduled. If all `N` slots are
+    /// in use, returns `id` back as an error.
+    pub fn schedule_once(&mut self, id: Id, ms: u32) -> Result<(), Id> {
+        self.schedule(id, ms, None)
+    }
+
+    /// Schedules `id` to go off every `period_ms` milliseconds, starting
+    /// `period_ms` from now.
+    ///
+    /// If `id` is already scheduled, it’s rescheduled. If all `N` slots are
+    /// in use, or `period_ms` is 0 (which would keep going off without time
+    /// ever moving on), returns `id` back as an error.
+    pub fn schedule_periodic(&mut self, id: Id, period_ms: u32) -> Result<(), Id> {
+        if period_ms == 0 {
+            return Err(id);
+        }
+
+        self.schedule(id, period_ms, Some(period_ms))
+    }
+
+    fn schedule(&mut self, id: Id, ms: u32, period_ms: Option<u32>) -> Result<(), Id> {
+        let target = self.now_ms() + u64::from(ms);
+
+        let slot = match self.position(&id) {
+            Some(i) => Some(i),
+            None => self.entries.iter().position(Option::is_none),
+        };
+
+        match slot {
+            Some(i) => {
+                self.entries[i] = Some(Entry {
+                    id,
+                    target,
+                    period_ms,
+                    seq: self.next_seq(),
+                });
+                Ok(())
+            }
+            None => Err(id),
+        }
+    }
+
+    /// Removes `id`. Returns false if it wasn’t scheduled.
+    pub fn cancel(&mut self, id: &Id) -> bool {
+        match self.position(id) {
+            Some(i) => {
+                self.entries[i] = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if `id` is scheduled.
+    pub fn is_scheduled(&self, id: &Id) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Advances time by `ms` milliseconds, yielding each timer that goes off
+    /// along the way.
+    ///
+    /// Time moves forward as the iterator is consumed. If it’s dropped early,
+    /// the rest of the run still happens, but the timers that go off aren’t
+    /// reported.
+    pub fn run_for(&mut self, ms: u32) -> SimRun<'_, Id, N> {
+        let until_ms = Some(self.now_ms() + u64::from(ms));
+
+        SimRun {
+            sim: self,
+            until_ms,
+        }
+    }
+
+    /// Advances time until no one-shot timers are left, yielding each timer
+    /// that goes off along the way. Periodic timers go off in between, but
+    /// don’t keep the simulation from being idle.
+    ///
+    /// Time moves forward as the iterator is consumed, and stops at the last
+    /// one-shot timer. As with [`run_for`](#method.run_for), dropping it early
+    /// still finishes the run.
+    pub fn run_until_idle(&mut self) -> SimRun<'_, Id, N> {
+        SimRun {
+            sim: self,
+            until_ms: None,
+        }
+    }
+
+    /// Fires the next timer that’s due by `until_ms`, or by the last one-shot
+    /// timer if that’s `None`.
+    fn step(&mut self, until_ms: Option<u64>) -> Option<Fired<Id>> {
+        let until_ms = match until_ms {
+            Some(until_ms) => until_ms,
+            None => self
+                .entries
+                .iter()
+                .flatten()
+                .filter(|entry| entry.period_ms.is_none())
+                .map(|entry| entry.target + 1)
+                .max()?,
+        };
+
+        let (i, at_ms) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| entry.as_ref().map(|entry| (i, entry)))
+            .map(|(i, entry)| (i, entry.target + 1, entry.seq))
+            .filter(|&(_, at_ms, _)| at_ms <= until_ms)
+            .min_by_key(|&(_, at_ms, seq)| (at_ms, seq))
+            .map(|(i, at_ms, _)| (i, at_ms))?;
+
+        self.advance_to(at_ms);
+        let seq = self.next_seq();
+
+        let id = match &mut self.entries[i] {
+            Some(Entry {
+                id,
+                target,
+                period_ms: Some(period_ms),
+                seq: entry_seq,
+            }) => {
+                *target += u64::from(*period_ms);
+                *entry_seq = seq;
+                id.clone()
+            }
+            entry => entry.take()?.id,
+        };
+
+        Some(Fired { id, at_ms })
+    }
+
+    fn advance_to(&self, ms: u64) {
+        if ms > self.now_ms() {
+            self.clock.set_ms(ms);
+        }
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.next_seq += 1;
+        self.next_seq
+    }
+
+    fn position(&self, id: &Id) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| matches!(entry, Some(entry) if entry.id == *id))
+    }
+}
+
+impl<Id: Clone + PartialEq, const N: usize> Default for SimClock<Id, N> {
+    fn default() -> Self {
+        SimClock::new()
+    }
+}
+
+/// Timers going off during a [`SimClock`](struct.SimClock.html) run
+///
+/// Returned by [`run_for`](struct.SimClock.html#method.run_for) and
+/// [`run_until_idle`](struct.SimClock.html#method.run_until_idle).
+pub struct SimRun<'a, Id: Clone + PartialEq, const N: usize> {
+    sim: &'a mut SimClock<Id, N>,
+    /// `None` to run until there are no one-shot timers.
+    until_ms: Option<u64>,
+}
+
+impl<'a, Id: Clone + PartialEq, const N: usize> Iterator for SimRun<'a, Id, N> {
+    type Item = Fired<Id>;
+
+    fn next(&mut self) -> Option<Fired<Id>> {
+        match self.sim.step(self.until_ms) {
+            Some(fired) => Some(fired),
+            None => {
+                if let Some(until_ms) = self.until_ms {
+                    self.sim.advance_to(until_ms);
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<'a, Id: Clone + PartialEq, const N: usize> Drop for SimRun<'a, Id, N> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
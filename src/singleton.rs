@@ -0,0 +1,53 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m::peripheral::SYST;
+
+use crate::{critical, PollingSysTick, SysTickCalibration, SysTickPeripheral};
+
+/// Set once a `PollingSysTick` has been made with `take` or `steal`.
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+impl PollingSysTick {
+    /// Like [`new`](#method.new), but only succeeds once per boot.
+    ///
+    /// `SYST` can be stolen from `cortex_m::Peripherals`, so owning one
+    /// doesn’t prove that nothing else has a `PollingSysTick` on the same
+    /// hardware. If every `PollingSysTick` in the program comes from `take`,
+    /// the second attempt gets `syst` back (as a `SYST`) as an error instead
+    /// of quietly reconfiguring SysTick out from under the first.
+    pub fn take<P: SysTickPeripheral>(
+        syst: P,
+        calibration: &SysTickCalibration,
+    ) -> Result<Self, SYST> {
+        let syst = syst.into_syst();
+        let taken = critical::free(|_| {
+            let taken = TAKEN.load(Ordering::Relaxed);
+            TAKEN.store(true, Ordering::Relaxed);
+            taken
+        });
+
+        if taken {
+            Err(syst)
+        } else {
+            Ok(Self::new(syst, calibration))
+        }
+    }
+
+    /// Steals SysTick and configures it like [`new`](#method.new), whether or
+    /// not a `PollingSysTick` already exists. Later calls to
+    /// [`take`](#method.take) will fail.
+    ///
+    /// This is for code that can’t get at the usual owner, such as an
+    /// application taking over from a bootloader, or a panic handler that
+    /// needs a delay.
+    ///
+    /// # Safety
+    ///
+    /// Any other `PollingSysTick`, or anything else using SysTick, must never
+    /// be used again, since SysTick is reset and its count starts over.
+    pub unsafe fn steal(calibration: &SysTickCalibration) -> Self {
+        critical::free(|_| TAKEN.store(true, Ordering::Relaxed));
+
+        Self::new(cortex_m::Peripherals::steal().SYST, calibration)
+    }
+}
@@ -0,0 +1,147 @@
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+
+use cortex_m::peripheral::{DCB, DWT};
+
+use crate::counter::Counter64;
+use crate::critical;
+use crate::reload::ticks_into_period;
+
+/// What [`PollingSysTick`](struct.PollingSysTick.html) should do about
+/// SysTick wraps that it missed because it wasn’t polled in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SlippageMode {
+    /// Only record them, so they can be read with
+    /// [`slippage`](struct.PollingSysTick.html#method.slippage).
+    Report,
+    /// Record them and also add them to the count, so that time doesn’t fall
+    /// behind.
+    Compensate,
+}
+
+/// How far a polled counter has fallen behind because it wasn’t polled often
+/// enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeSlippage {
+    /// Number of milliseconds that went by without SysTick being polled. If
+    /// slippage was being compensated for, these have since been added to the
+    /// count.
+    pub missed_ms: u32,
+}
+
+const MODE_OFF: u8 = 0;
+const MODE_REPORT: u8 = 1;
+const MODE_COMPENSATE: u8 = 2;
+
+/// Uses the DWT cycle counter to tell how many times SysTick really wrapped
+/// between two wraps that we saw.
+///
+/// When we see COUNTFLAG we work out the cycle count at which SysTick actually
+/// reloaded, from the cycle count now and how far SysTick has counted down
+/// since. The time between two of those reloads is a whole number of SysTick
+/// periods, so anything more than one period is wraps that we missed.
+pub(crate) struct WrapTracker {
+    mode: AtomicU8,
+    /// Cycle count of the last reload that we saw.
+    last_reload: AtomicU32,
+    missed: AtomicU32,
+}
+
+impl WrapTracker {
+    pub const fn new() -> Self {
+        WrapTracker {
+            mode: AtomicU8::new(MODE_OFF),
+            last_reload: AtomicU32::new(0),
+            missed: AtomicU32::new(0),
+        }
+    }
+
+    /// Starts the cycle counter and starts tracking from the current SysTick
+    /// period. Returns false if this core doesn’t have a cycle counter.
+    pub fn enable(&self, dcb: &mut DCB, dwt: &mut DWT, mode: SlippageMode, reload: u32) -> bool {
+        if !DWT::has_cycle_counter() {
+            return false;
+        }
+
+        dcb.enable_trace();
+        DWT::unlock();
+        dwt.enable_cycle_counter();
+
+        critical::free(|_| {
+            self.last_reload
+                .store(last_reload_cycles(reload), Ordering::Relaxed);
+            self.mode.store(
+                match mode {
+                    SlippageMode::Report => MODE_REPORT,
+                    SlippageMode::Compensate => MODE_COMPENSATE,
+                },
+                Ordering::Relaxed,
+            );
+        });
+
+        true
+    }
+
+    /// Starts tracking again from the current SysTick period, for when SysTick’s
+    /// period has been changed or restarted.
+    pub fn resync(&self, reload: u32) {
+        if self.mode.load(Ordering::Relaxed) != MODE_OFF {
+            self.last_reload
+                .store(last_reload_cycles(reload), Ordering::Relaxed);
+        }
+    }
+
+    /// Called after seeing COUNTFLAG set (and counting it). Records any wraps
+    /// that were missed before it, and adds them to `counter` if we’re
+    /// compensating. `tick_ms` is how many milliseconds each wrap counts for.
+    pub fn on_wrap(&self, counter: &Counter64, tick_ms: u32, reload: u32) {
+        let mode = self.mode.load(Ordering::Relaxed);
+
+        if mode == MODE_OFF {
+            return;
+        }
+
+        critical::free(|_| {
+            let reloaded_at = last_reload_cycles(reload);
+            let elapsed = reloaded_at.wrapping_sub(self.last_reload.load(Ordering::Relaxed));
+            let period = reload + 1;
+
+            // Rounded, since the reads above are a few cycles apart. In u64 so
+            // that a long stall, with `elapsed` near u32::MAX, can’t overflow.
+            let wraps = ((u64::from(elapsed) + u64::from(period / 2)) / u64::from(period)) as u32;
+            let missed = wraps.saturating_sub(1).saturating_mul(tick_ms);
+
+            self.last_reload.store(reloaded_at, Ordering::Relaxed);
+
+            if missed > 0 {
+                let total = self.missed.load(Ordering::Relaxed);
+                self.missed
+                    .store(total.saturating_add(missed), Ordering::Relaxed);
+
+                if mode == MODE_COMPENSATE {
+                    counter.add(missed);
+                }
+            }
+        });
+    }
+
+    pub fn slippage(&self) -> TimeSlippage {
+        TimeSlippage {
+            missed_ms: self.missed.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset(&self) {
+        self.missed.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Cycle count at which SysTick last reloaded, for a period that started from
+/// `reload`. Assumes that SysTick is clocked from the core clock.
+fn last_reload_cycles(reload: u32) -> u32 {
+    let cycles = DWT::cycle_count();
+    let counted_down = ticks_into_period(reload);
+
+    cycles.wrapping_sub(counted_down)
+}
@@ -0,0 +1,138 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+struct Channel<'a> {
+    output: &'a mut dyn FnMut(bool),
+    period_ms: u32,
+    on_ms: u32,
+    period_start: Wrapping<u32>,
+    level: Option<bool>,
+}
+
+/// Identifies a channel added to a [`SoftPwm`](struct.SoftPwm.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PwmChannel(usize);
+
+/// Errors from [`SoftPwm`](struct.SoftPwm.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SoftPwmError {
+    /// The `SoftPwm` already has `N` channels.
+    Full,
+}
+
+/// Slow PWM in software for up to `N` outputs
+///
+/// For pins without a hardware timer channel, and for things that only need
+/// periods of tens of milliseconds or more, like breathing LEDs or switching a
+/// heater. Add each output as a closure that sets it high or low, then call
+/// [`poll`](#method.poll) often from your main loop. Outputs are only called
+/// when their level changes.
+///
+/// ```ignore
+/// let mut set_heater = |on| if on { heater.set_high() } else { heater.set_low() };
+///
+/// let mut pwm: SoftPwm<_, 2> = SoftPwm::new(&systick);
+/// let heater_channel = pwm.add(1_000, 25, &mut set_heater).unwrap();
+///
+/// loop {
+///     pwm.poll();
+///     pwm.set_duty(heater_channel, pid.update(read_temp()));
+/// }
+/// ```
+///
+/// Each period starts with the output on for `duty` percent of it, then off
+/// for the rest, so the resolution is 1ms or 1% of the period, whichever is
+/// coarser.
+pub struct SoftPwm<'a, CM: CountsMillis, const N: usize> {
+    counter: &'a CM,
+    channels: [Option<Channel<'a>>; N],
+}
+
+impl<'a, CM: CountsMillis, const N: usize> SoftPwm<'a, CM, N> {
+    /// Creates a `SoftPwm` with no channels that times with `counter`.
+    pub fn new(counter: &'a CM) -> Self {
+        SoftPwm {
+            counter,
+            channels: [const { None }; N],
+        }
+    }
+
+    /// Adds an output with a period of `period_ms` that is on for `duty`
+    /// percent of it. Duties over 100 are treated as 100, and a `period_ms` of
+    /// 0 as 1.
+    pub fn add(
+        &mut self,
+        period_ms: u32,
+        duty: u8,
+        output: &'a mut dyn FnMut(bool),
+    ) -> Result<PwmChannel, SoftPwmError> {
+        let i = self
+            .channels
+            .iter()
+            .position(Option::is_none)
+            .ok_or(SoftPwmError::Full)?;
+
+        let period_ms = period_ms.max(1);
+
+        self.channels[i] = Some(Channel {
+            output,
+            period_ms,
+            on_ms: on_ms(period_ms, duty),
+            period_start: self.counter.count(),
+            level: None,
+        });
+
+        Ok(PwmChannel(i))
+    }
+
+    /// Changes a channel’s duty, taking effect right away. Returns false if
+    /// the channel has been removed.
+    pub fn set_duty(&mut self, channel: PwmChannel, duty: u8) -> bool {
+        match self.channels.get_mut(channel.0) {
+            Some(Some(ch)) => {
+                ch.on_ms = on_ms(ch.period_ms, duty);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a channel, leaving its output at whatever level it was last
+    /// set to. Returns false if it was already removed.
+    pub fn remove(&mut self, channel: PwmChannel) -> bool {
+        self.channels
+            .get_mut(channel.0)
+            .and_then(Option::take)
+            .is_some()
+    }
+
+    /// Switches any outputs that are due to change.
+    pub fn poll(&mut self) {
+        let now = self.counter.count();
+
+        for ch in self.channels.iter_mut().flatten() {
+            let mut elapsed = (now - ch.period_start).0;
+
+            if elapsed >= ch.period_ms {
+                let periods = elapsed / ch.period_ms;
+                ch.period_start += Wrapping(periods * ch.period_ms);
+                elapsed -= periods * ch.period_ms;
+            }
+
+            let level = elapsed < ch.on_ms;
+
+            if ch.level != Some(level) {
+                ch.level = Some(level);
+                (ch.output)(level);
+            }
+        }
+    }
+}
+
+fn on_ms(period_ms: u32, duty: u8) -> u32 {
+    let duty = u64::from(duty.min(100));
+    (u64::from(period_ms) * duty / 100) as u32
+}
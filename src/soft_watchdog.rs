@@ -0,0 +1,96 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Watchdog timer in software
+///
+/// [`feed`](#method.feed) it at least every `timeout_ms` milliseconds, or
+/// [`expired`](#method.expired) starts returning true. Useful when the
+/// hardware watchdog is already in use or can’t be set to the timeout you
+/// need.
+///
+/// Nothing happens on its own when the watchdog expires. Either check
+/// `expired` yourself, or give it a function to run with
+/// [`on_expire`](#method.on_expire) and call [`check`](#method.check)
+/// regularly, such as from a timer interrupt:
+///
+/// ```ignore
+/// let mut watchdog = SoftWatchdog::new(&systick, 500)
+///     .on_expire(|| panic!("main loop stalled"));
+///
+/// loop {
+///     watchdog.check();
+///     if work_done() {
+///         watchdog.feed();
+///     }
+/// }
+/// ```
+pub struct SoftWatchdog<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    timeout_ms: u32,
+    last_feed: Wrapping<u32>,
+    on_expire: Option<fn()>,
+    fired: bool,
+}
+
+impl<'a, CM: CountsMillis> SoftWatchdog<'a, CM> {
+    /// Creates a watchdog that expires if it isn’t fed within `timeout_ms`,
+    /// counting from now.
+    pub fn new(counter: &'a CM, timeout_ms: u32) -> Self {
+        SoftWatchdog {
+            counter,
+            timeout_ms,
+            last_feed: counter.count(),
+            on_expire: None,
+            fired: false,
+        }
+    }
+
+    /// Sets a function for [`check`](#method.check) to call when the watchdog
+    /// has expired. It can log, reset the chip, or panic.
+    pub fn on_expire(mut self, f: fn()) -> Self {
+        self.on_expire = Some(f);
+        self
+    }
+
+    /// Restarts the timeout from now.
+    pub fn feed(&mut self) {
+        self.last_feed = self.counter.count();
+        self.fired = false;
+    }
+
+    /// Returns true if it’s been longer than `timeout_ms` since the watchdog
+    /// was last fed.
+    pub fn expired(&self) -> bool {
+        self.since_feed_ms() > self.timeout_ms
+    }
+
+    /// Returns how many milliseconds are left before the watchdog expires, or
+    /// 0 if it already has.
+    pub fn remaining_ms(&self) -> u32 {
+        self.timeout_ms.saturating_sub(self.since_feed_ms())
+    }
+
+    /// Calls the [`on_expire`](#method.on_expire) function if the watchdog
+    /// has expired. Returns whether it has.
+    ///
+    /// The function is only called once per expiry, not again until the
+    /// watchdog has been fed and expired again.
+    pub fn check(&mut self) -> bool {
+        let expired = self.expired();
+
+        if expired && !self.fired {
+            self.fired = true;
+
+            if let Some(f) = self.on_expire {
+                f();
+            }
+        }
+
+        expired
+    }
+
+    fn since_feed_ms(&self) -> u32 {
+        (self.counter.count() - self.last_feed).0
+    }
+}
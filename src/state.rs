@@ -0,0 +1,115 @@
+//! Typestates for [`PollingSysTick`](struct.PollingSysTick.html), so that a
+//! stopped SysTick can’t be counted from.
+
+use core::marker::PhantomData;
+
+use cortex_m::peripheral::{syst::SystClkSource, SYST};
+
+use crate::{critical, CountsMillis, PollingSysTick};
+
+/// State of a [`PollingSysTick`](struct.PollingSysTick.html) whose SysTick is
+/// counting. This is the default, so plain `PollingSysTick` means
+/// `PollingSysTick<Running>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Running;
+
+/// State of a [`PollingSysTick`](struct.PollingSysTick.html) whose SysTick has
+/// been configured but isn’t counting.
+///
+/// A stopped `PollingSysTick` doesn’t implement
+/// [`CountsMillis`](trait.CountsMillis.html), so it can’t be used to start
+/// countdowns or read the time. Call [`start`](struct.PollingSysTick.html#method.start)
+/// to get a running one back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stopped;
+
+impl<S> PollingSysTick<S> {
+    pub(crate) fn into_state<T>(self) -> PollingSysTick<T> {
+        PollingSysTick {
+            syst: self.syst,
+            tick_ms: self.tick_ms,
+            calibration: self.calibration,
+            clock_source: self.clock_source,
+            counter: self.counter,
+            base_tick: self.base_tick,
+            trim_ppm: self.trim_ppm,
+            dither: self.dither,
+            partial_tick: self.partial_tick,
+            tickless: self.tickless,
+            #[cfg(not(armv6m))]
+            slip: self.slip,
+            #[cfg(feature = "stats")]
+            stats: self.stats,
+            #[cfg(feature = "eh0")]
+            deadline: self.deadline,
+            state: PhantomData,
+        }
+    }
+}
+
+impl PollingSysTick {
+    /// Stops SysTick, such as before entering a sleep mode that halts the
+    /// core clock, and gives back a `PollingSysTick<Stopped>` so that nothing
+    /// can read the count until it’s [`start`](#method.start)ed again.
+    ///
+    /// The count is brought up to date first. SysTick’s current value keeps
+    /// the partial tick that had gone by, so no time is lost when it’s
+    /// restarted.
+    ///
+    /// ```ignore
+    /// let stopped = systick.stop();
+    /// enter_stop_mode();
+    /// let systick = stopped.start_after_ms(rtc.elapsed_ms());
+    /// ```
+    pub fn stop(mut self) -> PollingSysTick<Stopped> {
+        critical::free(|_| {
+            self.count();
+            self.syst.disable_counter();
+        });
+
+        self.into_state()
+    }
+}
+
+impl PollingSysTick<Stopped> {
+    /// Restarts SysTick, keeping the count from before it was stopped.
+    pub fn start(self) -> PollingSysTick {
+        self.start_after_ms(0)
+    }
+
+    /// Restarts SysTick, first adding `slept_ms` to the count. Use this when
+    /// something else, like an RTC, measured how long the processor was
+    /// asleep.
+    pub fn start_after_ms(mut self, slept_ms: u32) -> PollingSysTick {
+        critical::free(|_| {
+            if slept_ms > 0 {
+                self.counter.add(slept_ms);
+            }
+
+            self.syst.enable_counter();
+
+            #[cfg(not(armv6m))]
+            self.slip.resync(self.dither.running_reload());
+
+            #[cfg(feature = "stats")]
+            self.stats.resync();
+        });
+
+        self.into_state()
+    }
+
+    /// Turns this value back into the underlying SysTick, along with the
+    /// count from when it was stopped. Same as
+    /// [`free`](struct.PollingSysTick.html#method.free) on a running one.
+    pub fn free(mut self) -> (SYST, u64) {
+        self.syst.disable_counter();
+        self.syst.disable_interrupt();
+        self.syst.set_clock_source(SystClkSource::Core);
+        self.syst.set_reload(0);
+        self.syst.clear_current();
+
+        (self.syst, self.counter.get64())
+    }
+}
@@ -0,0 +1,100 @@
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use cortex_m::peripheral::SYST;
+
+use crate::critical;
+
+/// How well a [`PollingSysTick`](struct.PollingSysTick.html) is being polled,
+/// from [`stats`](struct.PollingSysTick.html#method.stats)
+///
+/// Useful when delays run slow: if `wrap_loss_suspected` is set, `count` isn’t
+/// being called often enough, and `max_gap_ticks` shows by how much.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PollStats {
+    /// Number of SysTick wraps that polling has seen.
+    pub wraps: u32,
+    /// Longest time between two polls, in ticks of SysTick’s clock.
+    pub max_gap_ticks: u32,
+    /// True if two polls were ever a whole SysTick period or more apart, so
+    /// that SysTick may have wrapped more than once in between and lost time.
+    pub wrap_loss_suspected: bool,
+}
+
+/// Keeps `PollStats` up to date from each poll.
+///
+/// The gap between polls comes from SysTick’s current value at each one.
+/// Since COUNTFLAG only tells us that SysTick wrapped at least once, a gap is
+/// measured as if it wrapped at most once, so a gap of a whole period or more
+/// is where we start suspecting a lost wrap.
+pub(crate) struct PollTracker {
+    wraps: AtomicU32,
+    /// SysTick’s current value at the last poll.
+    last_current: AtomicU32,
+    max_gap: AtomicU32,
+    loss_suspected: AtomicBool,
+}
+
+impl PollTracker {
+    pub const fn new() -> Self {
+        PollTracker {
+            wraps: AtomicU32::new(0),
+            last_current: AtomicU32::new(0),
+            max_gap: AtomicU32::new(0),
+            loss_suspected: AtomicBool::new(false),
+        }
+    }
+
+    /// Called on every poll, with whether COUNTFLAG was set.
+    pub fn on_poll(&self, wrapped: bool) {
+        critical::free(|_| {
+            let current = SYST::get_current();
+            let last = self.last_current.load(Ordering::Relaxed);
+            let period = SYST::get_reload() + 1;
+
+            let gap = if wrapped {
+                self.wraps.store(
+                    self.wraps.load(Ordering::Relaxed).wrapping_add(1),
+                    Ordering::Relaxed,
+                );
+
+                last + period - current
+            } else {
+                last.saturating_sub(current)
+            };
+
+            if gap > self.max_gap.load(Ordering::Relaxed) {
+                self.max_gap.store(gap, Ordering::Relaxed);
+            }
+
+            if gap >= period {
+                self.loss_suspected.store(true, Ordering::Relaxed);
+            }
+
+            self.last_current.store(current, Ordering::Relaxed);
+        });
+    }
+
+    /// Starts measuring gaps from SysTick’s current value, for when it’s been
+    /// restarted or its period has changed.
+    pub fn resync(&self) {
+        self.last_current
+            .store(SYST::get_current(), Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> PollStats {
+        critical::free(|_| PollStats {
+            wraps: self.wraps.load(Ordering::Relaxed),
+            max_gap_ticks: self.max_gap.load(Ordering::Relaxed),
+            wrap_loss_suspected: self.loss_suspected.load(Ordering::Relaxed),
+        })
+    }
+
+    pub fn reset(&self) {
+        critical::free(|_| {
+            self.wraps.store(0, Ordering::Relaxed);
+            self.max_gap.store(0, Ordering::Relaxed);
+            self.loss_suspected.store(false, Ordering::Relaxed);
+        });
+    }
+}
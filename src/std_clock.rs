@@ -0,0 +1,60 @@
+use core::num::Wrapping;
+
+use std::time::Instant;
+
+use crate::{CountsMicros, CountsMillis, CountsMillis64};
+
+/// Counter backed by `std::time::Instant`, for running on the host
+///
+/// Integration tests and simulators can use this in place of a SysTick
+/// counter to run the same countdown code in real time. Its count starts at 0
+/// when it’s created.
+///
+/// ```ignore
+/// let clock = StdCountsMillis::new();
+/// let mut timeout = MillisCountDown::new(&clock);
+/// timeout.start_ms(50);
+/// nb::block!(timeout.wait_ms()).unwrap();
+/// ```
+pub struct StdCountsMillis {
+    start: Instant,
+}
+
+impl StdCountsMillis {
+    /// Creates a counter starting at 0 now.
+    pub fn new() -> Self {
+        StdCountsMillis {
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns the moment the count was 0.
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+}
+
+impl Default for StdCountsMillis {
+    fn default() -> Self {
+        StdCountsMillis::new()
+    }
+}
+
+impl CountsMillis for StdCountsMillis {
+    /// Returns the milliseconds since this was created.
+    fn count(&self) -> Wrapping<u32> {
+        Wrapping(self.count64() as u32)
+    }
+}
+
+impl CountsMillis64 for StdCountsMillis {
+    fn count64(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+impl CountsMicros for StdCountsMillis {
+    fn count_micros(&self) -> Wrapping<u32> {
+        Wrapping(self.start.elapsed().as_micros() as u32)
+    }
+}
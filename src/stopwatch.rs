@@ -0,0 +1,76 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Measures how long things take
+///
+/// [`start`](#method.start) it before an operation, then read
+/// [`elapsed_ms`](#method.elapsed_ms) afterwards. To time several steps in a
+/// row, call [`lap`](#method.lap) after each one.
+///
+/// ```ignore
+/// let mut stopwatch = Stopwatch::new(&systick);
+///
+/// stopwatch.start();
+/// flash.erase_sector(0);
+/// let erase_ms = stopwatch.lap();
+/// flash.write(0, &data);
+/// let write_ms = stopwatch.lap();
+/// ```
+///
+/// Times can be up to about 49.7 days before they wrap around.
+pub struct Stopwatch<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    started_millis: Option<Wrapping<u32>>,
+    lap_millis: Wrapping<u32>,
+}
+
+impl<'a, CM: CountsMillis> Stopwatch<'a, CM> {
+    /// Creates a stopwatch that isn’t running yet.
+    pub fn new(counter: &'a CM) -> Self {
+        Stopwatch {
+            counter,
+            started_millis: None,
+            lap_millis: Wrapping(0),
+        }
+    }
+
+    /// Starts timing from now. If the stopwatch was already running, it
+    /// starts over.
+    pub fn start(&mut self) {
+        let now = self.counter.count();
+
+        self.started_millis = Some(now);
+        self.lap_millis = now;
+    }
+
+    /// Returns true if the stopwatch has been started and not reset.
+    pub fn is_running(&self) -> bool {
+        self.started_millis.is_some()
+    }
+
+    /// Returns the milliseconds since [`start`](#method.start), or `None` if
+    /// the stopwatch isn’t running.
+    pub fn elapsed_ms(&self) -> Option<u32> {
+        let started = self.started_millis?;
+        Some((self.counter.count() - started).0)
+    }
+
+    /// Returns the milliseconds since the last lap (or since `start`, for the
+    /// first lap), and begins a new lap. Returns `None` if the stopwatch isn’t
+    /// running.
+    pub fn lap(&mut self) -> Option<u32> {
+        self.started_millis?;
+
+        let now = self.counter.count();
+        let lap = (now - self.lap_millis).0;
+        self.lap_millis = now;
+
+        Some(lap)
+    }
+
+    /// Stops the stopwatch, clearing its times.
+    pub fn reset(&mut self) {
+        self.started_millis = None;
+    }
+}
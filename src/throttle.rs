@@ -0,0 +1,94 @@
+use core::num::Wrapping;
+
+use crate::CountsMillis;
+
+/// Rate limiter for logging, radio transmissions, and the like
+///
+/// [`allow`](#method.allow) returns true at most once per `interval_ms`. Made
+/// with [`with_burst`](#method.with_burst), it works as a token bucket
+/// instead: up to `burst` calls in a row are allowed, and one more is earned
+/// back each `interval_ms`.
+///
+/// ```ignore
+/// let mut log_throttle = Throttle::new(&systick, 1_000);
+///
+/// if sensor_error && log_throttle.allow() {
+///     log::warn!("sensor read failed");
+/// }
+/// ```
+pub struct Throttle<'a, CM: CountsMillis> {
+    counter: &'a CM,
+    interval_ms: u32,
+    burst: u32,
+    tokens: u32,
+    last_refill: Wrapping<u32>,
+}
+
+impl<'a, CM: CountsMillis> Throttle<'a, CM> {
+    /// Creates a `Throttle` that allows one call per `interval_ms`, starting
+    /// with the first.
+    pub fn new(counter: &'a CM, interval_ms: u32) -> Self {
+        Throttle::with_burst(counter, interval_ms, 1)
+    }
+
+    /// Creates a `Throttle` that allows bursts of up to `burst` calls, earning
+    /// back one every `interval_ms`. It starts out full.
+    pub fn with_burst(counter: &'a CM, interval_ms: u32, burst: u32) -> Self {
+        Throttle {
+            counter,
+            interval_ms,
+            burst,
+            tokens: burst,
+            last_refill: counter.count(),
+        }
+    }
+
+    /// Returns true if a call is allowed right now, using it up if so.
+    pub fn allow(&mut self) -> bool {
+        if self.interval_ms == 0 {
+            return true;
+        }
+
+        self.refill();
+
+        if self.tokens > 0 {
+            self.tokens -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns how many calls would be allowed right now.
+    pub fn available(&mut self) -> u32 {
+        self.refill();
+        self.tokens
+    }
+
+    /// Fills the bucket back up, as if no calls had been made.
+    pub fn reset(&mut self) {
+        self.tokens = self.burst;
+        self.last_refill = self.counter.count();
+    }
+
+    fn refill(&mut self) {
+        let now = self.counter.count();
+
+        if self.tokens >= self.burst {
+            // Time spent full doesn’t earn anything, so start counting now.
+            self.last_refill = now;
+            return;
+        }
+
+        let earned = (now - self.last_refill).0 / self.interval_ms.max(1);
+
+        if earned > 0 {
+            self.tokens = self.tokens.saturating_add(earned).min(self.burst);
+            self.last_refill += Wrapping(earned.wrapping_mul(self.interval_ms));
+
+            if self.tokens == self.burst {
+                self.last_refill = now;
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis};
+
+/// What a [`TimedFsm`](struct.TimedFsm.html) state does if it lasts too long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StateTimeout<S> {
+    /// How long the state can last, in milliseconds.
+    pub after_ms: u32,
+    /// The state to move to once it has.
+    pub next: S,
+}
+
+/// State machine whose states can time out into other states
+///
+/// The states are your own `Copy` type, usually an enum. Each state’s timeout
+/// comes from a function you provide, so the timing of a whole handshake is
+/// written down in one place instead of in countdowns scattered around the
+/// code. Move between states yourself with
+/// [`transition`](#method.transition) when events happen, and call
+/// [`poll`](#method.poll) often to take the timeout transitions.
+///
+/// For example, sending a packet and waiting up to 500ms for an ACK, three
+/// times before giving up:
+///
+/// ```ignore
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Link {
+///     Idle,
+///     WaitAck(u8),
+///     Failed,
+/// }
+///
+/// fn timeouts(state: Link) -> Option<StateTimeout<Link>> {
+///     match state {
+///         Link::WaitAck(attempt) if attempt < 3 => Some(StateTimeout {
+///             after_ms: 500,
+///             next: Link::WaitAck(attempt + 1),
+///         }),
+///         Link::WaitAck(_) => Some(StateTimeout { after_ms: 500, next: Link::Failed }),
+///         _ => None,
+///     }
+/// }
+///
+/// let mut link = TimedFsm::new(&systick, Link::Idle, timeouts);
+///
+/// radio.send(&packet);
+/// link.transition(Link::WaitAck(1));
+///
+/// loop {
+///     if let Some(Link::WaitAck(_)) = link.poll() {
+///         radio.send(&packet);
+///     }
+///
+///     if radio.ack_received() {
+///         link.transition(Link::Idle);
+///     }
+/// }
+/// ```
+pub struct TimedFsm<'a, CM: CountsMillis, S: Copy> {
+    counter: &'a CM,
+    state: S,
+    entered: Wrapping<u32>,
+    timeouts: fn(S) -> Option<StateTimeout<S>>,
+    /// The current state’s timeout, looked up when it was entered.
+    timeout: Option<StateTimeout<S>>,
+}
+
+impl<'a, CM: CountsMillis, S: Copy> TimedFsm<'a, CM, S> {
+    /// Starts in `initial`, timed with `counter`. `timeouts` returns each
+    /// state’s timeout, or `None` for states that can last forever.
+    pub fn new(counter: &'a CM, initial: S, timeouts: fn(S) -> Option<StateTimeout<S>>) -> Self {
+        TimedFsm {
+            counter,
+            state: initial,
+            entered: counter.count(),
+            timeouts,
+            timeout: timeouts(initial),
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    /// Moves to `next`, starting its timeout from now. Moving to the state
+    /// it’s already in starts the timeout over.
+    pub fn transition(&mut self, next: S) {
+        self.state = next;
+        self.entered = self.counter.count();
+        self.timeout = (self.timeouts)(next);
+    }
+
+    /// Returns how long it’s been in the current state, in milliseconds.
+    pub fn time_in_state_ms(&self) -> u32 {
+        (self.counter.count() - self.entered).0
+    }
+
+    /// Takes the current state’s timeout transition if it’s due, and returns
+    /// the state it moved to, so that you can run that state’s entry action.
+    /// Otherwise returns `None`.
+    ///
+    /// Takes at most one transition per call, even if the next state’s
+    /// timeout has also passed.
+    pub fn poll(&mut self) -> Option<S> {
+        let timeout = self.timeout?;
+
+        if !has_passed(
+            self.counter.count(),
+            self.entered + Wrapping(timeout.after_ms),
+        ) {
+            return None;
+        }
+
+        self.transition(timeout.next);
+        Some(timeout.next)
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{CountsMillis, Instant, MillisCountDown};
+
+/// Error from an `nb` operation run with a timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TimeoutError<E> {
+    /// The operation was still returning `WouldBlock` when time ran out.
+    TimedOut,
+    /// The operation failed with its own error.
+    Other(E),
+}
+
+/// Repeatedly polls the `nb` operation `f` until it finishes or `ms`
+/// milliseconds have passed.
+///
+/// ```ignore
+/// let byte = with_timeout_ms(&systick, 100, || serial.read())?;
+/// ```
+///
+/// Returns `TimeoutError::TimedOut` if time ran out, or
+/// `TimeoutError::Other` if `f` returned an error of its own.
+pub fn with_timeout_ms<CM, T, E, F>(counter: &CM, ms: u32, f: F) -> Result<T, TimeoutError<E>>
+where
+    CM: CountsMillis,
+    F: FnMut() -> nb::Result<T, E>,
+{
+    wait_with_deadline(counter, Instant::now(counter) + ms, f)
+}
+
+/// Repeatedly polls the `nb` operation `f` until it finishes or the count
+/// passes `deadline`.
+///
+/// Like [`with_timeout_ms`](fn.with_timeout_ms.html), but several steps can
+/// share one deadline, so that a whole transaction or a bus recovery sequence
+/// is bounded rather than each step on its own:
+///
+/// ```ignore
+/// let deadline = Instant::now(&systick) + 50;
+///
+/// wait_with_deadline(&systick, deadline, || spi.send(command))?;
+/// let reply = wait_with_deadline(&systick, deadline, || spi.read())?;
+/// ```
+pub fn wait_with_deadline<CM, T, E, F>(
+    counter: &CM,
+    deadline: Instant,
+    mut f: F,
+) -> Result<T, TimeoutError<E>>
+where
+    CM: CountsMillis,
+    F: FnMut() -> nb::Result<T, E>,
+{
+    let mut count_down = MillisCountDown::new(counter);
+    count_down.start_until(deadline);
+
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(nb::Error::Other(err)) => return Err(TimeoutError::Other(err)),
+            Err(nb::Error::WouldBlock) => {
+                if count_down.try_wait_ms().is_ok() {
+                    return Err(TimeoutError::TimedOut);
+                }
+            }
+        }
+    }
+}
+
+/// Error from [`retry_ms`](fn.retry_ms.html), saying which limit was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RetryError<E> {
+    /// The overall timeout passed.
+    TimedOut,
+    /// Every attempt failed. Holds the error from the last one, or `None` if it
+    /// ran out of time instead.
+    OutOfAttempts(Option<E>),
+}
+
+/// Polls the `nb` operation `op` until it succeeds, retrying it up to
+/// `attempts` times.
+///
+/// Each attempt fails if `op` returns an error or is still returning
+/// `WouldBlock` after `per_try_ms`. All of the attempts together are limited
+/// to `total_ms`. `op` is passed the number of the attempt, starting at 0, so
+/// it can tell when to start the operation over.
+///
+/// ```ignore
+/// let reading = retry_ms(&systick, 1_000, 100, 5, |attempt| {
+///     if attempt != last_attempt {
+///         last_attempt = attempt;
+///         sensor.start_measurement()?;
+///     }
+///
+///     sensor.read_measurement()
+/// });
+/// ```
+pub fn retry_ms<CM, T, E, F>(
+    counter: &CM,
+    total_ms: u32,
+    per_try_ms: u32,
+    attempts: u32,
+    mut op: F,
+) -> Result<T, RetryError<E>>
+where
+    CM: CountsMillis,
+    F: FnMut(u32) -> nb::Result<T, E>,
+{
+    let mut total = MillisCountDown::new(counter);
+    total.start_ms(total_ms);
+
+    let mut last_error = None;
+
+    for attempt in 0..attempts {
+        let mut per_try = MillisCountDown::new(counter);
+        per_try.start_ms(per_try_ms);
+
+        last_error = loop {
+            match op(attempt) {
+                Ok(value) => return Ok(value),
+                Err(nb::Error::Other(err)) => break Some(err),
+                Err(nb::Error::WouldBlock) => {
+                    if total.try_wait_ms().is_ok() {
+                        return Err(RetryError::TimedOut);
+                    }
+
+                    if per_try.try_wait_ms().is_ok() {
+                        break None;
+                    }
+                }
+            }
+        };
+
+        if total.try_wait_ms().is_ok() {
+            return Err(RetryError::TimedOut);
+        }
+    }
+
+    Err(RetryError::OutOfAttempts(last_error))
+}
+
+/// Method version of [`with_timeout_ms`](fn.with_timeout_ms.html) for `nb`
+/// operations.
+///
+/// ```ignore
+/// let byte = (|| serial.read()).timeout_ms(&systick, 100)?;
+/// ```
+pub trait TimeoutExt<T, E> {
+    /// Repeatedly polls this operation until it finishes or `ms` milliseconds
+    /// have passed.
+    fn timeout_ms<CM: CountsMillis>(self, counter: &CM, ms: u32) -> Result<T, TimeoutError<E>>;
+}
+
+impl<T, E, F: FnMut() -> nb::Result<T, E>> TimeoutExt<T, E> for F {
+    fn timeout_ms<CM: CountsMillis>(self, counter: &CM, ms: u32) -> Result<T, TimeoutError<E>> {
+        with_timeout_ms(counter, ms, self)
+    }
+}
@@ -0,0 +1,66 @@
+use core::cell::RefCell;
+use core::num::Wrapping;
+
+use embedded_hal::timer::{CountDown, Periodic};
+
+use crate::counter::Counter64;
+use crate::{CountsMillis, CountsMillis64};
+
+/// Millisecond counter based on any periodic embedded-hal 0.2 `CountDown`
+///
+/// Use this when SysTick is reserved for something else but your HAL has a
+/// hardware timer to spare. The timer is started with a 1ms period, and each
+/// time it finishes a period the count goes up by one, so code written against
+/// [`MillisCountDown`](struct.MillisCountDown.html) runs unchanged on it.
+///
+/// ## Implementation
+///
+/// Like [`PollingSysTick`](struct.PollingSysTick.html), this counts by
+/// polling the timer’s `wait` method, so [`count`](#method.count) must be
+/// called at least once per millisecond to keep an accurate count.
+pub struct TimerCountsMillis<T> {
+    timer: RefCell<T>,
+    counter: Counter64,
+}
+
+impl<T: CountDown + Periodic> TimerCountsMillis<T> {
+    /// Starts `timer` with a period of `one_ms`, which should be however your
+    /// HAL spells 1 millisecond in the timer’s `Time` type.
+    pub fn new<D: Into<T::Time>>(mut timer: T, one_ms: D) -> Self {
+        timer.start(one_ms);
+
+        TimerCountsMillis {
+            timer: RefCell::new(timer),
+            counter: Counter64::new(),
+        }
+    }
+
+    /// Turns this value back into the underlying timer. The timer is left
+    /// running.
+    pub fn free(self) -> T {
+        self.timer.into_inner()
+    }
+}
+
+impl<T: CountDown + Periodic> CountsMillis for TimerCountsMillis<T> {
+    /// Returns a number that goes up no faster than once per millisecond. Like
+    /// `PollingSysTick`, this only counts while it is being polled.
+    fn count(&self) -> Wrapping<u32> {
+        // The RefCell makes this type !Sync, so nothing else can be polling
+        // the timer or incrementing the count at the same time.
+        if self.timer.borrow_mut().wait().is_ok() {
+            self.counter.increment();
+        }
+
+        self.counter.get()
+    }
+}
+
+impl<T: CountDown + Periodic> CountsMillis64 for TimerCountsMillis<T> {
+    /// 64-bit version of [`count`](#method.count). Has the same requirement
+    /// that it be polled at least once per millisecond.
+    fn count64(&self) -> u64 {
+        self.count();
+        self.counter.get64()
+    }
+}
@@ -0,0 +1,174 @@
+use core::num::Wrapping;
+
+use crate::{has_passed, CountsMillis, Instant};
+
+struct Entry<Id> {
+    id: Id,
+    target: Wrapping<u32>,
+    /// `None` for one-shot timers.
+    period_ms: Option<u32>,
+}
+
+/// Fixed-capacity queue of many one-shot and periodic timers
+///
+/// Each timer is identified by an `Id` of your choosing, such as an enum of the
+/// events in a protocol state machine. Call [`poll`](#method.poll) (or
+/// [`poll_with`](#method.poll_with)) regularly to find out which timers have
+/// expired, rather than checking a separate
+/// [`MillisCountDown`](struct.MillisCountDown.html) for each one.
+///
+/// Holds up to `N` timers, with no heap allocation.
+///
+/// ```ignore
+/// let mut timers: TimerQueue<_, Event, 8> = TimerQueue::new(&systick);
+///
+/// timers.schedule_periodic(Event::Heartbeat, 1_000).unwrap();
+/// timers.schedule_once(Event::AckTimeout, 250).unwrap();
+///
+/// loop {
+///     timers.poll_with(|event| handle(event));
+/// }
+/// ```
+///
+/// Periodic timers are re-armed from when they were due, not from when they
+/// were polled, so they don’t drift. Like other timers in this crate, deadlines
+/// must be less than about 24.8 days away.
+pub struct TimerQueue<'a, CM: CountsMillis, Id, const N: usize> {
+    counter: &'a CM,
+    entries: [Option<Entry<Id>>; N],
+}
+
+impl<'a, CM: CountsMillis, Id: Clone + PartialEq, const N: usize> TimerQueue<'a, CM, Id, N> {
+    /// Creates an empty queue that times with `counter`.
+    pub fn new(counter: &'a CM) -> Self {
+        TimerQueue {
+            counter,
+            entries: [const { None }; N],
+        }
+    }
+
+    /// Schedules `id` to expire once, `ms` milliseconds from now.
+    ///
+    /// If `id` is already scheduled, it’s rescheduled. If the queue is full,
+    /// returns `id` back as an error.
+    pub fn schedule_once(&mut self, id: Id, ms: u32) -> Result<(), Id> {
+        self.schedule(id, ms, None)
+    }
+
+    /// Schedules `id` to expire every `period_ms` milliseconds, starting
+    /// `period_ms` from now.
+    ///
+    /// If `id` is already scheduled, it’s rescheduled. If the queue is full,
+    /// or `period_ms` is 0 (which would always be due), returns `id` back as
+    /// an error.
+    pub fn schedule_periodic(&mut self, id: Id, period_ms: u32) -> Result<(), Id> {
+        if period_ms == 0 {
+            return Err(id);
+        }
+
+        self.schedule(id, period_ms, Some(period_ms))
+    }
+
+    fn schedule(&mut self, id: Id, ms: u32, period_ms: Option<u32>) -> Result<(), Id> {
+        let target = self.counter.count() + Wrapping(ms);
+
+        let slot = match self.position(&id) {
+            Some(i) => Some(i),
+            None => self.entries.iter().position(Option::is_none),
+        };
+
+        match slot {
+            Some(i) => {
+                self.entries[i] = Some(Entry {
+                    id,
+                    target,
+                    period_ms,
+                });
+                Ok(())
+            }
+            None => Err(id),
+        }
+    }
+
+    /// Removes `id` from the queue. Returns false if it wasn’t scheduled.
+    pub fn cancel(&mut self, id: &Id) -> bool {
+        match self.position(id) {
+            Some(i) => {
+                self.entries[i] = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if `id` is scheduled.
+    pub fn is_scheduled(&self, id: &Id) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Returns the number of scheduled timers.
+    pub fn len(&self) -> usize {
+        self.entries.iter().flatten().count()
+    }
+
+    /// Returns true if no timers are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns when the next timer is due, or `None` if the queue is empty.
+    ///
+    /// Useful for deciding how long it’s safe to sleep for.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let now = self.counter.count();
+
+        self.entries
+            .iter()
+            .flatten()
+            .min_by_key(|entry| (entry.target - now).0 as i32)
+            .map(|entry| Instant::from_millis(entry.target.0))
+    }
+
+    /// Returns the ID of a timer that has expired, or `None` if none have.
+    ///
+    /// If several have expired, the most overdue is returned first, so call
+    /// this in a loop until it returns `None`. One-shot timers are removed from
+    /// the queue, and periodic ones are re-armed for their next period.
+    pub fn poll(&mut self) -> Option<Id> {
+        let now = self.counter.count();
+
+        let (i, _) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| entry.as_ref().map(|entry| (i, entry)))
+            .filter(|(_, entry)| has_passed(now, entry.target))
+            .max_by_key(|(_, entry)| (now - entry.target).0 as i32)?;
+
+        match &mut self.entries[i] {
+            Some(Entry {
+                id,
+                target,
+                period_ms: Some(period_ms),
+            }) => {
+                *target += Wrapping(*period_ms);
+                Some(id.clone())
+            }
+            entry => entry.take().map(|entry| entry.id),
+        }
+    }
+
+    /// Calls `f` with the ID of each timer that has expired, in the same order
+    /// as [`poll`](#method.poll).
+    pub fn poll_with<F: FnMut(Id)>(&mut self, mut f: F) {
+        while let Some(id) = self.poll() {
+            f(id);
+        }
+    }
+
+    fn position(&self, id: &Id) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| matches!(entry, Some(entry) if entry.id == *id))
+    }
+}
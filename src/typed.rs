@@ -0,0 +1,124 @@
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
+
+use embedded_hal::timer::{Cancel, CountDown};
+
+use void::Void;
+
+use crate::{ignore_not_started, CountDownError, CountsMillis, MillisCountDown, MAX_COUNTDOWN_MS};
+
+/// Types that can be a [`TypedMillisCountDown`](struct.TypedMillisCountDown.html)’s
+/// `CountDown::Time`.
+pub trait CountDownTime {
+    /// Converts the time to a `u32` of milliseconds, limited to
+    /// [`MAX_COUNTDOWN_MS`](constant.MAX_COUNTDOWN_MS.html) so that a long time
+    /// can’t wrap into a short countdown.
+    fn to_millis(self) -> u32;
+}
+
+impl CountDownTime for Duration {
+    fn to_millis(self) -> u32 {
+        crate::eh0::duration_ms(self)
+    }
+}
+
+/// A plain number of milliseconds.
+impl CountDownTime for u32 {
+    fn to_millis(self) -> u32 {
+        self.min(MAX_COUNTDOWN_MS)
+    }
+}
+
+#[cfg(feature = "fugit")]
+impl CountDownTime for fugit::MillisDurationU32 {
+    fn to_millis(self) -> u32 {
+        self.ticks().min(MAX_COUNTDOWN_MS)
+    }
+}
+
+/// `MillisCountDown` whose `CountDown::Time` is `u32` milliseconds, so
+/// `start(500u32)` works without any `u64` math.
+pub type MillisCountDownU32<'a, CM> = TypedMillisCountDown<'a, CM, u32>;
+
+/// `MillisCountDown` whose `CountDown::Time` is a fugit
+/// `MillisDurationU32`, for HALs that use fugit durations.
+#[cfg(feature = "fugit")]
+pub type MillisCountDownFugit<'a, CM> = TypedMillisCountDown<'a, CM, fugit::MillisDurationU32>;
+
+/// [`MillisCountDown`](struct.MillisCountDown.html) with a choice of
+/// `CountDown::Time` type
+///
+/// `MillisCountDown`’s `CountDown` implementation takes `core::time::Duration`s,
+/// which are `u64`s underneath. Drivers that are generic over `CountDown` with
+/// some other `Time` can use one of these instead, usually through the
+/// [`MillisCountDownU32`](type.MillisCountDownU32.html) or
+/// `MillisCountDownFugit` aliases:
+///
+/// ```ignore
+/// let mut count_down = MillisCountDownU32::new(&systick);
+///
+/// count_down.start(500u32);
+/// nb::block!(count_down.wait()).unwrap();
+/// ```
+///
+/// It derefs to the `MillisCountDown` it wraps, for all of its other methods.
+pub struct TypedMillisCountDown<'a, CM: CountsMillis, T: CountDownTime> {
+    count_down: MillisCountDown<'a, CM>,
+    time: PhantomData<T>,
+}
+
+impl<'a, CM: CountsMillis, T: CountDownTime> TypedMillisCountDown<'a, CM, T> {
+    /// Creates a countdown from a `CountsMillis` source.
+    pub fn new(counter: &'a CM) -> Self {
+        TypedMillisCountDown {
+            count_down: MillisCountDown::new(counter),
+            time: PhantomData,
+        }
+    }
+
+    /// Returns the underlying `MillisCountDown`.
+    pub fn into_inner(self) -> MillisCountDown<'a, CM> {
+        self.count_down
+    }
+}
+
+impl<'a, CM: CountsMillis, T: CountDownTime> Deref for TypedMillisCountDown<'a, CM, T> {
+    type Target = MillisCountDown<'a, CM>;
+
+    fn deref(&self) -> &MillisCountDown<'a, CM> {
+        &self.count_down
+    }
+}
+
+impl<'a, CM: CountsMillis, T: CountDownTime> DerefMut for TypedMillisCountDown<'a, CM, T> {
+    fn deref_mut(&mut self) -> &mut MillisCountDown<'a, CM> {
+        &mut self.count_down
+    }
+}
+
+impl<'a, CM: CountsMillis, T: CountDownTime> CountDown for TypedMillisCountDown<'a, CM, T> {
+    type Time = T;
+
+    /// Starts timing the given time. Calling this method before the time has
+    /// fully elapsed will reset the timer.
+    fn start<U>(&mut self, count: U)
+    where
+        U: Into<T>,
+    {
+        self.count_down.start_ms(count.into().to_millis());
+    }
+
+    /// Same as `MillisCountDown`’s `wait`.
+    fn wait(&mut self) -> Result<(), nb::Error<Void>> {
+        ignore_not_started(self.count_down.try_wait_ms())
+    }
+}
+
+impl<'a, CM: CountsMillis, T: CountDownTime> Cancel for TypedMillisCountDown<'a, CM, T> {
+    type Error = CountDownError;
+
+    fn cancel(&mut self) -> Result<(), CountDownError> {
+        self.count_down.cancel()
+    }
+}
@@ -0,0 +1,59 @@
+//! Implementations of ufmt’s `uDisplay` trait, enabled by the `ufmt` feature.
+
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+use crate::{
+    CalibrationError, CountDownError, CountsMillis, Instant, MillisCountDown, SysTickCalibration,
+    TimeoutError,
+};
+
+impl uDisplay for Instant {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        uwrite!(f, "{}ms", self.millis())
+    }
+}
+
+impl uDisplay for SysTickCalibration {
+    /// Shows the clock frequency that the calibration is for, like `48000000Hz`.
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        uwrite!(f, "{}Hz", self.clock_hz())
+    }
+}
+
+impl<'a, CM: CountsMillis> uDisplay for MillisCountDown<'a, CM> {
+    /// Shows when the countdown finishes, without reading the counter.
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        match self.deadline() {
+            Some(deadline) => uwrite!(f, "running until {}", deadline),
+            None => f.write_str("not running"),
+        }
+    }
+}
+
+impl uDisplay for CountDownError {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(match self {
+            CountDownError::NotStarted => "countdown not started",
+            CountDownError::TooLong => "countdown too long",
+        })
+    }
+}
+
+impl uDisplay for CalibrationError {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(match self {
+            CalibrationError::ClockTooSlow => "clock too slow for SysTick",
+            CalibrationError::ReloadTooLarge => "reload too large for SysTick",
+            CalibrationError::NoReferenceClock => "no SysTick reference clock",
+        })
+    }
+}
+
+impl<E: uDisplay> uDisplay for TimeoutError<E> {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        match self {
+            TimeoutError::TimedOut => f.write_str("timed out"),
+            TimeoutError::Other(err) => err.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Timestamps for the `log` crate, enabled by the `log` feature.
+
+use core::fmt;
+
+use log::{Log, Metadata, Record};
+
+use crate::global;
+
+/// Time since the global counter started, for log timestamps
+///
+/// Displays as seconds with millisecond precision, like `12.345`. Get one from
+/// [`uptime`](fn.uptime.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uptime {
+    millis: u32,
+}
+
+impl Uptime {
+    /// Returns the uptime in milliseconds.
+    pub fn as_millis(self) -> u32 {
+        self.millis
+    }
+}
+
+impl fmt::Display for Uptime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:03}", self.millis / 1_000, self.millis % 1_000)
+    }
+}
+
+/// Returns the time from [`global::millis`](global/fn.millis.html), for
+/// putting in front of log messages.
+///
+/// Use this in your own `log::Log` implementation, or wrap it in an
+/// [`UptimeLogger`](struct.UptimeLogger.html) to have it done for you. For
+/// finer timestamps, use [`global::micros`](global/fn.micros.html) directly.
+pub fn uptime() -> Uptime {
+    Uptime {
+        millis: global::millis(),
+    }
+}
+
+/// `log::Log` that puts the [`uptime`](fn.uptime.html) in front of each
+/// message before passing it on to another logger
+///
+/// ```ignore
+/// static LOGGER: UptimeLogger<RttLogger> = UptimeLogger::new(RttLogger);
+///
+/// global::install(systick).ok();
+/// log::set_logger(&LOGGER).unwrap();
+/// log::info!("ready"); // "[1.234] ready"
+/// ```
+pub struct UptimeLogger<L> {
+    inner: L,
+}
+
+impl<L: Log> UptimeLogger<L> {
+    /// Wraps `inner`. `const`, so that the result can be a `static`.
+    pub const fn new(inner: L) -> Self {
+        UptimeLogger { inner }
+    }
+
+    /// Returns the wrapped logger.
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+}
+
+impl<L: Log> Log for UptimeLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.inner.log(
+            &Record::builder()
+                .args(format_args!("[{}] {}", uptime(), record.args()))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
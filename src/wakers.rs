@@ -0,0 +1,63 @@
+//! Table of wakers waiting on the millisecond count, shared by the `async` and
+//! `embassy` features.
+//!
+//! Futures register their wakers here along with the millisecond that they’re
+//! waiting for, and the SysTick exception handler (via
+//! [`InterruptSysTick::on_interrupt`](../struct.InterruptSysTick.html#method.on_interrupt))
+//! wakes them once the count reaches it.
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use crate::critical::{self, Mutex};
+
+/// How many wakers can be waiting at once. Callers need a fallback (such as
+/// asking to be polled again immediately) for when this is full.
+const WAKER_SLOTS: usize = 8;
+
+type Slot = Option<(u64, Waker)>;
+
+static WAKERS: Mutex<RefCell<[Slot; WAKER_SLOTS]>> =
+    Mutex::new(RefCell::new([const { None }; WAKER_SLOTS]));
+
+/// Stores `waker` to be woken once the 64-bit count reaches `at`.
+///
+/// If the waker is already waiting it keeps the earlier of the two times, since
+/// one task may be waiting on more than one timer. Waking early is harmless: the
+/// task will poll, see that it’s not done, and register again.
+///
+/// Returns `false` if there was no room for it.
+pub(crate) fn register(at: u64, waker: &Waker) -> bool {
+    critical::free(|cs| {
+        let mut slots = WAKERS.borrow(cs).borrow_mut();
+
+        if let Some((existing_at, _)) = slots.iter_mut().flatten().find(|(_, w)| w.will_wake(waker))
+        {
+            *existing_at = (*existing_at).min(at);
+            return true;
+        }
+
+        match slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some((at, waker.clone()));
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+/// Wakes (and forgets) all of the wakers whose times have been reached.
+///
+/// Called from the SysTick exception handler.
+pub(crate) fn wake_expired(now: u64) {
+    critical::free(|cs| {
+        for slot in WAKERS.borrow(cs).borrow_mut().iter_mut() {
+            if matches!(slot, Some((at, _)) if *at <= now) {
+                if let Some((_, waker)) = slot.take() {
+                    waker.wake();
+                }
+            }
+        }
+    });
+}
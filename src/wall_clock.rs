@@ -0,0 +1,140 @@
+use crate::CountsMillis64;
+
+/// How fast a `slew` is applied by default, in milliseconds of correction per
+/// second. 1ms/s is 0.1%, slow enough that timeouts based on the wall clock
+/// aren’t noticeably affected.
+const DEFAULT_SLEW_MS_PER_S: u32 = 1;
+
+/// Errors from [`WallClock`](struct.WallClock.html) operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WallClockError {
+    /// The time hasn’t been set yet with
+    /// [`set_time`](struct.WallClock.html#method.set_time).
+    NotSet,
+}
+
+/// Approximate civil time, kept as an offset from a 64-bit millisecond counter
+///
+/// The counter only knows how long it’s been since it was created. Once you
+/// learn the actual time from somewhere (GPS, NTP over a serial link, a host
+/// computer), give it to [`set_time`](#method.set_time) and `WallClock` will
+/// keep answering [`now_unix_ms`](#method.now_unix_ms) from then on.
+///
+/// Later corrections can either be applied all at once with
+/// [`adjust`](#method.adjust), which can make time jump backwards, or
+/// gradually with [`slew`](#method.slew), which keeps time moving forward by
+/// running the clock slightly fast or slow until the correction has been
+/// made.
+///
+/// Accuracy is only as good as the counter’s calibration, so plan on
+/// correcting it periodically.
+pub struct WallClock<'a, CM: CountsMillis64> {
+    counter: &'a CM,
+    /// Unix time minus the counter’s count, once the time has been set.
+    offset_ms: Option<i64>,
+    /// Slew correction that hasn’t been folded into `offset_ms` yet.
+    slew_ms: i64,
+    /// Count when `slew_ms` started being applied.
+    slew_start: u64,
+    slew_ms_per_s: u32,
+}
+
+impl<'a, CM: CountsMillis64> WallClock<'a, CM> {
+    /// Creates a `WallClock` whose time has not been set yet.
+    pub fn new(counter: &'a CM) -> Self {
+        WallClock {
+            counter,
+            offset_ms: None,
+            slew_ms: 0,
+            slew_start: 0,
+            slew_ms_per_s: DEFAULT_SLEW_MS_PER_S,
+        }
+    }
+
+    /// Returns true once [`set_time`](#method.set_time) has been called.
+    pub fn is_set(&self) -> bool {
+        self.offset_ms.is_some()
+    }
+
+    /// Returns the current time in milliseconds since the Unix epoch, or `None`
+    /// if the time hasn’t been set.
+    pub fn now_unix_ms(&self) -> Option<u64> {
+        let now = self.counter.count64();
+
+        self.offset_ms
+            .map(|offset| (now as i64 + offset + self.applied_slew(now)).max(0) as u64)
+    }
+
+    /// Sets the current time, in milliseconds since the Unix epoch.
+    ///
+    /// Cancels any slew that’s in progress.
+    pub fn set_time(&mut self, unix_ms: u64) {
+        let now = self.counter.count64();
+
+        self.offset_ms = Some(unix_ms as i64 - now as i64);
+        self.slew_ms = 0;
+    }
+
+    /// Immediately moves the time forward (or backward, if negative) by
+    /// `delta_ms`.
+    pub fn adjust(&mut self, delta_ms: i64) -> Result<(), WallClockError> {
+        let offset = self.fold_slew()?;
+
+        self.offset_ms = Some(offset + delta_ms);
+        Ok(())
+    }
+
+    /// Gradually moves the time forward (or backward, if negative) by
+    /// `delta_ms`, at the rate set by
+    /// [`set_slew_rate`](#method.set_slew_rate).
+    ///
+    /// Replaces any slew that’s in progress, though whatever part of it has
+    /// already been applied is kept.
+    pub fn slew(&mut self, delta_ms: i64) -> Result<(), WallClockError> {
+        self.fold_slew()?;
+
+        self.slew_ms = delta_ms;
+        Ok(())
+    }
+
+    /// Returns how much of the current slew is still left to apply.
+    pub fn remaining_slew_ms(&self) -> i64 {
+        self.slew_ms - self.applied_slew(self.counter.count64())
+    }
+
+    /// Sets how fast [`slew`](#method.slew) corrections are applied, in
+    /// milliseconds of correction per second. The default is 1ms/s.
+    ///
+    /// Values are clamped to between 1 and 999, so that time always keeps
+    /// moving forward.
+    pub fn set_slew_rate(&mut self, ms_per_s: u32) {
+        // Keep what’s been applied at the old rate before changing it.
+        let _ = self.fold_slew();
+
+        self.slew_ms_per_s = ms_per_s.clamp(1, 999);
+    }
+
+    /// How much of `slew_ms` has been applied by the count `now`.
+    fn applied_slew(&self, now: u64) -> i64 {
+        let elapsed = now.saturating_sub(self.slew_start);
+        let max = (elapsed.saturating_mul(u64::from(self.slew_ms_per_s)) / 1_000)
+            .min(i64::MAX as u64) as i64;
+
+        self.slew_ms.clamp(-max, max)
+    }
+
+    /// Moves the part of the slew that has been applied so far into the
+    /// offset, and restarts the slew from now. Returns the new offset.
+    fn fold_slew(&mut self) -> Result<i64, WallClockError> {
+        let now = self.counter.count64();
+        let offset = self.offset_ms.ok_or(WallClockError::NotSet)?;
+        let applied = self.applied_slew(now);
+
+        self.offset_ms = Some(offset + applied);
+        self.slew_ms -= applied;
+        self.slew_start = now;
+
+        Ok(offset + applied)
+    }
+}